@@ -17,7 +17,7 @@ use {
         },
         engines::{
             setup_logging,
-            run_experiment_off_policy,
+            run_experiment_hgb,
             ParamEnv,
             ParamAlg,
             HgbGUI,
@@ -87,7 +87,7 @@ fn main() -> Result<()> {
     } else {
         //// Run Algorithm as Experiment ////
 
-        run_experiment_off_policy::<DDPG_HGB<PointEnv>, PointEnv, _, _>(
+        run_experiment_hgb::<DDPG_HGB<PointEnv>, PointEnv, _, _>(
             &args.name,
             args.n_repetitions,
             ParamEnv::AsConfig(match args.env_config {
@@ -0,0 +1,100 @@
+use {
+    graph_rl::{
+        util::read_config,
+        cli::{
+            ArgLoglevel,
+            ArgDevice,
+            Args,
+        },
+        agents::{
+            Algorithm,
+            DDPG,
+        },
+        envs::{
+            Environment,
+            PointEnv,
+            PointEnvConfig,
+        },
+        configs::{
+            DDPG_Config,
+            TrainConfig,
+        },
+        engines::{
+            setup_logging,
+            loop_off_policy_parallel,
+        },
+    },
+    candle_core::{
+        Device,
+        CudaDevice,
+        backend::BackendDevice,
+    },
+    clap::Parser,
+    anyhow::Result,
+    tracing::Level,
+};
+
+/// The number of rollout worker threads.
+const N_WORKERS: usize = 8;
+/// The number of training iterations between weight broadcasts to the workers.
+const SYNC_EVERY: usize = 50;
+
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    setup_logging(
+        &args.name,
+        match args.log {
+            ArgLoglevel::Error => Some(Level::ERROR),
+            ArgLoglevel::Warn => Some(Level::WARN),
+            ArgLoglevel::Info => Some(Level::INFO),
+            ArgLoglevel::None => None,
+        },
+    )?;
+
+    let device = match args.device {
+        ArgDevice::Cpu => Device::Cpu,
+        ArgDevice::Cuda => Device::Cuda(CudaDevice::new(0)?),
+    };
+
+    let env_config: PointEnvConfig = match args.env_config {
+        Some(env_config) => read_config(env_config)?,
+        None => PointEnvConfig::default(),
+    };
+    let alg_config: DDPG_Config = match args.alg_config {
+        Some(alg_config) => read_config(alg_config)?,
+        None => DDPG_Config::default(),
+    };
+    let train_config: TrainConfig = match args.train_config {
+        Some(train_config) => read_config(train_config)?,
+        None => TrainConfig::default(),
+    };
+
+    let env = <PointEnv as Environment>::new(env_config.clone())?;
+    let mut alg = *DDPG::from_config(
+        &device,
+        &alg_config,
+        env.observation_space().iter().product::<usize>(),
+        env.action_space().iter().product::<usize>(),
+    )?;
+    alg.seed(train_config.seed())?;
+
+    let (mc_returns, successes) = loop_off_policy_parallel::<_, PointEnv, _, _>(
+        &env_config,
+        &mut alg,
+        N_WORKERS,
+        SYNC_EVERY,
+        train_config,
+        &device,
+    )?;
+
+    println!(
+        "Finished {} episodes with an average return of {:.3} and a success rate of {:.3}",
+        mc_returns.len(),
+        mc_returns.iter().sum::<f64>() / mc_returns.len() as f64,
+        successes.iter().filter(|&&s| s).count() as f64 / successes.len() as f64,
+    );
+
+    Ok(())
+}
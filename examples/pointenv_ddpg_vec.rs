@@ -0,0 +1,92 @@
+use {
+    graph_rl::{
+        util::read_config,
+        cli::{
+            ArgLoglevel,
+            ArgDevice,
+            Args,
+        },
+        agents::{
+            Algorithm,
+            DDPG,
+        },
+        envs::{
+            Environment,
+            PointEnv,
+            PointEnvConfig,
+            VecEnv,
+        },
+        configs::{
+            DDPG_Config,
+            TrainConfig,
+        },
+        engines::{
+            setup_logging,
+            loop_off_policy_vec,
+        },
+    },
+    candle_core::{
+        Device,
+        CudaDevice,
+        backend::BackendDevice,
+    },
+    clap::Parser,
+    anyhow::Result,
+    tracing::Level,
+};
+
+/// The number of environments that are stepped together.
+const N_ENVS: usize = 16;
+
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    setup_logging(
+        &args.name,
+        match args.log {
+            ArgLoglevel::Error => Some(Level::ERROR),
+            ArgLoglevel::Warn => Some(Level::WARN),
+            ArgLoglevel::Info => Some(Level::INFO),
+            ArgLoglevel::None => None,
+        },
+    )?;
+
+    let device = match args.device {
+        ArgDevice::Cpu => Device::Cpu,
+        ArgDevice::Cuda => Device::Cuda(CudaDevice::new(0)?),
+    };
+
+    let env_config: PointEnvConfig = match args.env_config {
+        Some(env_config) => read_config(env_config)?,
+        None => PointEnvConfig::default(),
+    };
+    let alg_config: DDPG_Config = match args.alg_config {
+        Some(alg_config) => read_config(alg_config)?,
+        None => DDPG_Config::default(),
+    };
+    let train_config: TrainConfig = match args.train_config {
+        Some(train_config) => read_config(train_config)?,
+        None => TrainConfig::default(),
+    };
+
+    let mut envs = VecEnv::<PointEnv>::from_config(env_config, N_ENVS)?;
+    let mut alg = *DDPG::from_config(
+        &device,
+        &alg_config,
+        envs.envs()[0].observation_space().iter().product::<usize>(),
+        envs.envs()[0].action_space().iter().product::<usize>(),
+    )?;
+    alg.seed(train_config.seed())?;
+
+    let (mc_returns, successes) = loop_off_policy_vec(&mut envs, &mut alg, train_config, &device)?;
+
+    println!(
+        "Finished {} episodes with an average return of {:.3} and a success rate of {:.3}",
+        mc_returns.len(),
+        mc_returns.iter().sum::<f64>() / mc_returns.len() as f64,
+        successes.iter().filter(|&&s| s).count() as f64 / successes.len() as f64,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use {
+    graph_rl::{
+        util::read_config,
+        envs::{
+            remote::{
+                serve,
+                serve_tcp,
+            },
+            Environment,
+            PointEnv,
+            PointEnvConfig,
+        },
+        engines::setup_logging,
+    },
+    clap::Parser,
+    anyhow::Result,
+    std::io::{
+        stdin,
+        stdout,
+    },
+    tracing::Level,
+};
+
+/// Serve a PointEnv to a RemoteEnv, over stdin and stdout or over TCP.
+#[derive(Parser, Debug, Clone)]
+#[command(author, version, about, long_about = None)]
+struct ServerArgs {
+    /// Environment config.
+    #[arg(long)]
+    env_config: Option<String>,
+
+    /// Listen on this address (e.g. 127.0.0.1:7878) instead of serving a
+    /// single client over stdin and stdout.
+    #[arg(long)]
+    tcp: Option<String>,
+
+    /// Name to use for the log directory.
+    #[arg(long, default_value = "pointenv_server")]
+    name: String,
+}
+
+
+fn main() -> Result<()> {
+    let args = ServerArgs::parse();
+
+    // stdout is reserved for the protocol, so only log to the file
+    setup_logging(&args.name, Some(Level::WARN))?;
+
+    let config = match args.env_config {
+        Some(env_config) => read_config(env_config)?,
+        None => PointEnvConfig::default(),
+    };
+
+    match args.tcp {
+        Some(address) => serve_tcp::<PointEnv, _>(config, address),
+        None => {
+            let mut env = *<PointEnv as Environment>::new(config)?;
+            serve(&mut env, &mut stdin().lock(), &mut stdout().lock())
+        },
+    }
+}
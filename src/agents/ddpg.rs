@@ -3,13 +3,29 @@ use {
         RunMode,
         Algorithm,
         OffPolicyAlgorithm,
+        ParallelAlgorithm,
         SaveableAlgorithm,
     },
     crate::{
-        configs::DDPG_Config,
+        configs::{
+            DDPG_Config,
+            NoiseConfig,
+            EncoderConfig,
+        },
         components::{
+            derive_seed,
+            ExplorationNoise,
+            NoiseKind,
+            NoiseSchedule,
             OuNoise,
+            GaussianNoise,
+            EpsilonRandomNoise,
+            ParameterNoise,
             ReplayBuffer,
+            ReplayMixture,
+            ReplaySource,
+            ConvEncoder,
+            EncoderKind,
         },
     },
     candle_core::{
@@ -30,36 +46,99 @@ use {
         VarBuilder,
         VarMap,
     },
-    tracing::info,
+    rand::{
+        rngs::StdRng,
+        Rng,
+        SeedableRng,
+    },
+    rand_distr::Normal,
+    tracing::{
+        info,
+        warn,
+    },
     std::path::Path,
 };
 
+/// Soft-update all the variables of the target network towards those of the
+/// network, i.e. `target = tau * network + (1 - tau) * target`.
+///
+/// Variables are matched by name, with `target_prefix` in place of `network_prefix`.
 fn track(
     varmap: &mut VarMap,
-    vb: &VarBuilder,
     target_prefix: &str,
     network_prefix: &str,
-    dims: &[(usize, usize)],
     tau: f64,
 ) -> Result<()> {
-    for (i, &(in_dim, out_dim)) in dims.iter().enumerate() {
-        let target_w = vb.get((out_dim, in_dim), &format!("{target_prefix}-fc{i}.weight"))?;
-        let network_w = vb.get((out_dim, in_dim), &format!("{network_prefix}-fc{i}.weight"))?;
-        varmap.set_one(
-            format!("{target_prefix}-fc{i}.weight"),
-            ((tau * network_w)? + ((1.0 - tau) * target_w)?)?,
-        )?;
+    let mut updates = Vec::new();
+    {
+        let data = varmap.data().lock().unwrap();
+        for (name, network_var) in data.iter() {
+            let Some(suffix) = name.strip_prefix(&format!("{network_prefix}-")) else {
+                continue
+            };
+            let target_name = format!("{target_prefix}-{suffix}");
+            let Some(target_var) = data.get(&target_name) else {
+                continue
+            };
+            let value = (network_var.as_tensor().affine(tau, 0.0)?
+                + target_var.as_tensor().affine(1.0 - tau, 0.0)?)?;
+            updates.push((target_name, value));
+        }
+    }
+    for (name, value) in updates {
+        varmap.set_one(name, value)?;
+    }
+    Ok(())
+}
 
-        let target_b = vb.get(out_dim, &format!("{target_prefix}-fc{i}.bias"))?;
-        let network_b = vb.get(out_dim, &format!("{network_prefix}-fc{i}.bias"))?;
-        varmap.set_one(
-            format!("{target_prefix}-fc{i}.bias"),
-            ((tau * network_b)? + ((1.0 - tau) * target_b)?)?,
-        )?;
+/// Re-initialize all the weights and biases in `varmap` from a seeded random
+/// number generator.
+///
+/// This mirrors the initialization of [`candle_nn::linear`], i.e. Kaiming
+/// normal weights and uniform biases in `$\pm 1 / \sqrt{\text{fan\_in}}$`,
+/// but does not depend on the unseedable RNG of the CPU backend.
+fn reinitialize(
+    varmap: &VarMap,
+    seed: u64,
+) -> Result<()> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let data = varmap.data().lock().unwrap();
+
+    // iterate in a fixed order, the HashMap order is random
+    let mut names: Vec<&String> = data.keys().collect();
+    names.sort();
+
+    for name in names {
+        let var = &data[name];
+        let values: Vec<f64> = match name.strip_suffix(".bias") {
+            Some(layer) => {
+                let weight = &data[&format!("{layer}.weight")];
+                let fan_in = (weight.elem_count() / weight.dims()[0]) as f64;
+                let bound = 1.0 / fan_in.sqrt();
+                (0..var.elem_count()).map(|_| rng.gen_range(-bound..bound)).collect()
+            },
+            None => {
+                let fan_in = (var.elem_count() / var.dims()[0]) as f64;
+                let normal = Normal::new(0.0, (2.0 / fan_in).sqrt()).map_err(Error::wrap)?;
+                (0..var.elem_count()).map(|_| rng.sample(normal)).collect()
+            },
+        };
+        var.set(&Tensor::from_vec(values, var.shape(), var.device())?.to_dtype(var.dtype())?)?;
     }
     Ok(())
 }
 
+/// Pass the state through the encoder, if there is one.
+fn encode(
+    encoder: Option<&ConvEncoder>,
+    state: &Tensor,
+) -> Result<Tensor> {
+    match encoder {
+        Some(encoder) => encoder.forward(state),
+        None => Ok(state.clone()),
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Clone)]
 struct Actor<'a> {
@@ -67,6 +146,8 @@ struct Actor<'a> {
     vb: VarBuilder<'a>,
     network: Vec<Linear>,
     target_network: Vec<Linear>,
+    encoder: Option<ConvEncoder>,
+    target_encoder: Option<ConvEncoder>,
     dims: Vec<(usize, usize)>,
 }
 
@@ -75,10 +156,17 @@ impl Actor<'_> {
         device: &Device,
         dtype: DType,
         dims: &[(usize, usize)],
+        encoder: &EncoderConfig,
+        size_state: usize,
     ) -> Result<Self> {
         let mut varmap = VarMap::new();
         let vb = VarBuilder::from_varmap(&varmap, dtype, device);
 
+        let make_encoder = |prefix: &str| match encoder.kind {
+            EncoderKind::None => Ok(None),
+            EncoderKind::Conv => ConvEncoder::new(vb.pp(format!("{prefix}-enc")), encoder, size_state).map(Some),
+        };
+
         let make_layers = |prefix: &str| {
             let layers = vec![
                 linear(
@@ -105,49 +193,79 @@ impl Actor<'_> {
 
         let network = make_layers("actor")?;
         let target_network = make_layers("target-actor")?;
+        let encoder = make_encoder("actor")?;
+        let target_encoder = make_encoder("target-actor")?;
 
         // this sets the two networks to be equal to each other using tau = 1.0
-        track(&mut varmap, &vb, "target-actor", "actor", dims, 1.0)?;
+        track(&mut varmap, "target-actor", "actor", 1.0)?;
 
         Ok(Self {
             varmap,
             vb,
             network,
             target_network,
+            encoder,
+            target_encoder,
             dims: dims.to_vec(),
         })
     }
 
-    fn forward(
-        &self,
+    fn forward_layers(
+        layers: &[Linear],
+        encoder: Option<&ConvEncoder>,
         state: &Tensor,
     ) -> Result<Tensor> {
-        let mut xs = state.clone();
+        let mut xs = encode(encoder, state)?;
 
-        xs = self.network[0].forward(&xs)?;
+        xs = layers[0].forward(&xs)?;
         xs = xs.relu()?;
-        xs = self.network[1].forward(&xs)?;
+        xs = layers[1].forward(&xs)?;
         xs = xs.relu()?;
-        xs = self.network[2].forward(&xs)?;
+        xs = layers[2].forward(&xs)?;
         xs = xs.tanh()?;
 
         Ok(xs)
     }
 
+    fn forward(
+        &self,
+        state: &Tensor,
+    ) -> Result<Tensor> {
+        Self::forward_layers(&self.network, self.encoder.as_ref(), state)
+    }
+
     fn target_forward(
         &self,
         state: &Tensor,
     ) -> Result<Tensor> {
-        let mut xs = state.clone();
+        Self::forward_layers(&self.target_network, self.target_encoder.as_ref(), state)
+    }
 
-        xs = self.target_network[0].forward(&xs)?;
-        xs = xs.relu()?;
-        xs = self.target_network[1].forward(&xs)?;
-        xs = xs.relu()?;
-        xs = self.target_network[2].forward(&xs)?;
-        xs = xs.tanh()?;
+    /// Get the weights and biases of the network, layer by layer.
+    fn parameters(&self) -> Vec<Tensor> {
+        self.network
+            .iter()
+            .flat_map(|layer| [Some(layer.weight().clone()), layer.bias().cloned()])
+            .flatten()
+            .collect()
+    }
 
-        Ok(xs)
+    /// Build layers from the weights and biases given by [`Actor::parameters`].
+    fn layers_from_parameters(parameters: Vec<Tensor>) -> Vec<Linear> {
+        let mut parameters = parameters.into_iter();
+        let mut layers = Vec::new();
+        while let Some((weight, bias)) = parameters.next().zip(parameters.next()) {
+            layers.push(Linear::new(weight, Some(bias)));
+        }
+        layers
+    }
+
+    fn seed(
+        &mut self,
+        seed: u64,
+    ) -> Result<()> {
+        reinitialize(&self.varmap, seed)?;
+        self.track(1.0)
     }
 
     fn track(
@@ -156,10 +274,8 @@ impl Actor<'_> {
     ) -> Result<()> {
         track(
             &mut self.varmap,
-            &self.vb,
             "target-actor",
             "actor",
-            &self.dims,
             tau,
         )
     }
@@ -172,6 +288,8 @@ struct Critic<'a> {
     vb: VarBuilder<'a>,
     network: Vec<Linear>,
     target_network: Vec<Linear>,
+    encoder: Option<ConvEncoder>,
+    target_encoder: Option<ConvEncoder>,
     dims: Vec<(usize, usize)>,
 }
 
@@ -180,10 +298,17 @@ impl Critic<'_> {
         device: &Device,
         dtype: DType,
         dims: &[(usize, usize)],
+        encoder: &EncoderConfig,
+        size_state: usize,
     ) -> Result<Self> {
         let mut varmap = VarMap::new();
         let vb = VarBuilder::from_varmap(&varmap, dtype, device);
 
+        let make_encoder = |prefix: &str| match encoder.kind {
+            EncoderKind::None => Ok(None),
+            EncoderKind::Conv => ConvEncoder::new(vb.pp(format!("{prefix}-enc")), encoder, size_state).map(Some),
+        };
+
         let make_layers = |prefix: &str| {
             let layers = vec![
                 linear(
@@ -209,15 +334,19 @@ impl Critic<'_> {
 
         let network = make_layers("critic")?;
         let target_network = make_layers("target-critic")?;
+        let encoder = make_encoder("critic")?;
+        let target_encoder = make_encoder("target-critic")?;
 
         // this sets the two networks to be equal to each other using tau = 1.0
-        track(&mut varmap, &vb, "target-critic", "critic", dims, 1.0)?;
+        track(&mut varmap, "target-critic", "critic", 1.0)?;
 
         Ok(Self {
             varmap,
             vb,
             network,
             target_network,
+            encoder,
+            target_encoder,
             dims: dims.to_vec(),
         })
     }
@@ -227,7 +356,8 @@ impl Critic<'_> {
         state: &Tensor,
         action: &Tensor,
     ) -> Result<Tensor> {
-        let mut xs = Tensor::cat(&[action, state], 1)?;
+        let state = encode(self.encoder.as_ref(), state)?;
+        let mut xs = Tensor::cat(&[action, &state], 1)?;
 
         xs = self.network[0].forward(&xs)?;
         xs = xs.relu()?;
@@ -243,7 +373,8 @@ impl Critic<'_> {
         state: &Tensor,
         action: &Tensor,
     ) -> Result<Tensor> {
-        let mut xs = Tensor::cat(&[action, state], 1)?;
+        let state = encode(self.target_encoder.as_ref(), state)?;
+        let mut xs = Tensor::cat(&[action, &state], 1)?;
 
         xs = self.target_network[0].forward(&xs)?;
         xs = xs.relu()?;
@@ -254,21 +385,59 @@ impl Critic<'_> {
         Ok(xs)
     }
 
+    fn seed(
+        &mut self,
+        seed: u64,
+    ) -> Result<()> {
+        reinitialize(&self.varmap, seed)?;
+        self.track(1.0)
+    }
+
     fn track(
         &mut self,
         tau: f64,
     ) -> Result<()> {
         track(
             &mut self.varmap,
-            &self.vb,
             "target-critic",
             "critic",
-            &self.dims,
             tau,
         )
     }
 }
 
+fn build_noise(
+    config: &NoiseConfig,
+    size_action: usize,
+    device: &Device,
+) -> Result<Box<dyn ExplorationNoise>> {
+    Ok(match config.kind {
+        NoiseKind::OrnsteinUhlenbeck => Box::new(OuNoise::new(
+            config.ou_theta,
+            config.ou_kappa,
+            config.ou_sigma,
+            size_action,
+            device,
+        )?),
+        NoiseKind::Gaussian => Box::new(GaussianNoise::new(config.gaussian_sigma)),
+        // the random actions cover the range of the tanh output of the actor
+        NoiseKind::EpsilonRandom => Box::new(EpsilonRandomNoise::new(
+            config.epsilon,
+            vec![-1.0..=1.0; size_action],
+        )),
+        NoiseKind::ParameterSpace => Box::new(ParameterNoise::new(config.parameter_sigma)),
+    })
+}
+
+fn build_noise_schedule(config: &NoiseConfig) -> NoiseSchedule {
+    NoiseSchedule::new(
+        config.decay,
+        config.decay_unit,
+        config.decay_length,
+        config.final_scale,
+    )
+}
+
 #[allow(dead_code)]
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Clone)]
@@ -280,8 +449,15 @@ pub struct DDPG<'a> {
     gamma: f64,
     tau: f64,
     replay_buffer: ReplayBuffer,
+    replay_sources: ReplayMixture,
+    replay_online_ratio: f64,
+    pretrain_replay_ratio: f64,
     batch_size: usize,
-    ou_noise: OuNoise,
+    noise: Box<dyn ExplorationNoise>,
+    noise_schedule: NoiseSchedule,
+    perturbed_actor: Option<Vec<Linear>>,
+    seed: Option<u64>,
+    reseeds: u64,
 
     size_state: usize,
     size_action: usize,
@@ -303,7 +479,10 @@ impl DDPG<'_> {
         tau: f64,
         buffer_capacity: usize,
         batch_size: usize,
-        ou_noise: OuNoise,
+        replay_online_ratio: f64,
+        pretrain_replay_ratio: f64,
+        noise: NoiseConfig,
+        encoder: EncoderConfig,
     ) -> Result<Self> {
         let filter_by_prefix = |varmap: &VarMap, prefix: &str| {
             varmap
@@ -315,14 +494,22 @@ impl DDPG<'_> {
                 .collect::<Vec<Var>>()
         };
 
+        // the networks see the encoded state instead of the raw observation
+        let size_input = match encoder.kind {
+            EncoderKind::None => size_state,
+            EncoderKind::Conv => ConvEncoder::output_size(&encoder, size_state),
+        };
+
         let actor = Actor::new(
             device,
             DType::F64,
             &[
-                (size_state, hidden_1_size),
+                (size_input, hidden_1_size),
                 (hidden_1_size, hidden_2_size),
                 (hidden_2_size, size_action),
             ],
+            &encoder,
+            size_state,
         )?;
         let actor_optim = AdamW::new(
             filter_by_prefix(&actor.varmap, "actor"),
@@ -336,10 +523,12 @@ impl DDPG<'_> {
             device,
             DType::F64,
             &[
-                (size_state + size_action, hidden_1_size),
+                (size_input + size_action, hidden_1_size),
                 (hidden_1_size, hidden_2_size),
                 (hidden_2_size, 1),
             ],
+            &encoder,
+            size_state,
         )?;
         let critic_optim = AdamW::new(
             filter_by_prefix(&critic.varmap, "critic"),
@@ -349,10 +538,6 @@ impl DDPG<'_> {
             },
         )?;
 
-        let ou_theta = ou_noise.theta();
-        let ou_kappa = ou_noise.kappa();
-        let ou_sigma = ou_noise.sigma();
-
         Ok(Self {
             actor,
             actor_optim,
@@ -361,8 +546,15 @@ impl DDPG<'_> {
             gamma,
             tau,
             replay_buffer: ReplayBuffer::new(buffer_capacity),
+            replay_sources: ReplayMixture::new(),
+            replay_online_ratio,
+            pretrain_replay_ratio,
             batch_size,
-            ou_noise,
+            noise: build_noise(&noise, size_action, device)?,
+            noise_schedule: build_noise_schedule(&noise),
+            perturbed_actor: None,
+            seed: None,
+            reseeds: 0,
             size_state,
             size_action,
             device: device.clone(),
@@ -375,9 +567,10 @@ impl DDPG<'_> {
                 tau,
                 replay_buffer_capacity: buffer_capacity,
                 training_batch_size: batch_size,
-                ou_theta,
-                ou_kappa,
-                ou_sigma,
+                replay_online_ratio,
+                pretrain_replay_ratio,
+                noise,
+                encoder,
             },
         })
     }
@@ -400,13 +593,106 @@ impl DDPG<'_> {
         )?.squeeze(0)
     }
 
+    /// Derive a seed for a replacement buffer or noise from its own stream of
+    /// the algorithm seed, so that replacing them keeps a seeded run
+    /// reproducible. Returns `None` if the algorithm was never seeded.
+    fn replacement_seed(
+        &mut self,
+        stream: &str,
+    ) -> Option<u64> {
+        let seed = self.seed?;
+        self.reseeds += 1;
+        Some(derive_seed(seed, &format!("{stream}_{}", self.reseeds)))
+    }
+
+    /// An empty replay buffer, seeded from the algorithm seed.
+    fn seeded_buffer(
+        &mut self,
+        buffer_capacity: usize,
+    ) -> ReplayBuffer {
+        let mut buffer = ReplayBuffer::new(buffer_capacity);
+        if let Some(seed) = self.replacement_seed("replay_buffer") {
+            buffer.seed(seed);
+        }
+        buffer
+    }
+
     pub fn new_buffer(&mut self, buffer_capacity: usize) {
-        self.replay_buffer = ReplayBuffer::new(buffer_capacity);
+        self.replay_buffer = self.seeded_buffer(buffer_capacity);
+    }
+
+    /// Replace the replay buffer, e.g. with one loaded by [`ReplayBuffer::load`].
+    ///
+    /// The buffer is reseeded from the algorithm seed.
+    pub fn set_buffer(&mut self, mut buffer: ReplayBuffer) {
+        if let Some(seed) = self.replacement_seed("replay_buffer") {
+            buffer.seed(seed);
+        }
+        self.replay_buffer = buffer;
+    }
+
+    /// Reset the exploration noise and advance its schedule at the end of an
+    /// episode. This is called automatically when a terminal transition is
+    /// remembered.
+    pub fn end_episode(&mut self) {
+        self.noise.reset();
+        self.noise_schedule.end_episode();
+        self.perturbed_actor = None;
+    }
+
+    pub fn noise_scale(&self) -> f64 {
+        self.noise_schedule.scale()
     }
 
     pub fn set_buffer_capacity(&mut self, buffer_capacity: usize) {
         self.replay_buffer.set_capacity(buffer_capacity);
     }
+
+    pub fn replay_sources(&self) -> &[ReplaySource] {
+        self.replay_sources.sources()
+    }
+
+    /// Add a replay source, e.g. demonstrations, that is mixed into every
+    /// training batch with the given ratio (relative to `replay_online_ratio`).
+    pub fn add_replay_source(
+        &mut self,
+        name: &str,
+        mut buffer: ReplayBuffer,
+        ratio: f64,
+    ) {
+        if let Some(seed) = self.seed {
+            buffer.seed(derive_seed(seed, &format!("replay_source_{name}")));
+        }
+        self.replay_sources.add(name, buffer, ratio);
+    }
+
+    pub fn remove_replay_source(
+        &mut self,
+        name: &str,
+    ) -> Option<ReplayBuffer> {
+        self.replay_sources.remove(name)
+    }
+
+    pub fn set_replay_ratio(
+        &mut self,
+        name: &str,
+        ratio: f64,
+    ) -> bool {
+        self.replay_sources.set_ratio(name, ratio)
+    }
+
+    /// Move the current replay buffer into a replay source with the given
+    /// ratio, and continue with an empty buffer of the same capacity.
+    pub fn retain_buffer_as_source(
+        &mut self,
+        name: &str,
+        ratio: f64,
+    ) {
+        let capacity = self.config.replay_buffer_capacity;
+        let empty = self.seeded_buffer(capacity);
+        let buffer = std::mem::replace(&mut self.replay_buffer, empty);
+        self.replay_sources.add(name, buffer, ratio);
+    }
 }
 
 impl Algorithm for DDPG<'_> {
@@ -424,23 +710,28 @@ impl Algorithm for DDPG<'_> {
         self.tau = config.tau;
         self.set_buffer_capacity(config.replay_buffer_capacity);
         self.batch_size = config.training_batch_size;
+        self.replay_online_ratio = config.replay_online_ratio;
+        self.pretrain_replay_ratio = config.pretrain_replay_ratio;
 
         self.config.gamma = config.gamma;
         self.config.tau = config.tau;
         self.config.replay_buffer_capacity = config.replay_buffer_capacity;
         self.config.training_batch_size = config.training_batch_size;
+        self.config.replay_online_ratio = config.replay_online_ratio;
+        self.config.pretrain_replay_ratio = config.pretrain_replay_ratio;
 
-        if let Ok(noise) = OuNoise::new(
-            config.ou_theta,
-            config.ou_kappa,
-            config.ou_sigma,
+        if let Ok(noise) = build_noise(
+            &config.noise,
             self.size_action,
             &self.device,
         ) {
-            self.ou_noise = noise;
-            self.config.ou_theta = config.ou_theta;
-            self.config.ou_kappa = config.ou_kappa;
-            self.config.ou_sigma = config.ou_sigma;
+            self.noise = noise;
+            if let Some(seed) = self.replacement_seed("noise") {
+                self.noise.seed(seed);
+            }
+            self.noise_schedule = build_noise_schedule(&config.noise);
+            self.perturbed_actor = None;
+            self.config.noise = config.noise.clone();
         }
     }
 
@@ -462,34 +753,74 @@ impl Algorithm for DDPG<'_> {
             config.tau,
             config.replay_buffer_capacity,
             config.training_batch_size,
-            OuNoise::new(
-                config.ou_theta,
-                config.ou_kappa,
-                config.ou_sigma,
-                size_action,
-                device,
-            )?,
+            config.replay_online_ratio,
+            config.pretrain_replay_ratio,
+            config.noise.clone(),
+            config.encoder.clone(),
         )?))
     }
 
+    fn seed(
+        &mut self,
+        seed: u64,
+    ) -> Result<()> {
+        self.actor.seed(derive_seed(seed, "actor"))?;
+        self.critic.seed(derive_seed(seed, "critic"))?;
+        self.replay_buffer.seed(derive_seed(seed, "replay_buffer"));
+        for source in self.replay_sources.sources_mut() {
+            source.buffer.seed(derive_seed(seed, &format!("replay_source_{}", source.name)));
+        }
+        self.noise.seed(derive_seed(seed, "noise"));
+        self.seed = Some(seed);
+        self.reseeds = 0;
+        Ok(())
+    }
+
     fn actions(
         &mut self,
         state: &Tensor,
         mode: RunMode,
     ) -> Result<Tensor> {
         // Candle assumes a batch dimension, so when we don't have one we need
-        // to pretend we do by un- and resqueezing the state tensor.
-        let actions = self.actor.forward(&state.detach()?.unsqueeze(0)?)?.squeeze(0)?;
-        Ok(if let RunMode::Train = mode {
-            (actions + self.ou_noise.sample()?)?
-        } else {
-            actions
-        })
+        // to pretend we do by un- and resqueezing the state tensor. A batch of
+        // states, e.g. from a `VecEnv`, is passed through as a whole.
+        let batched = state.rank() > 1;
+        let state = if batched { state.detach()? } else { state.detach()?.unsqueeze(0)? };
+        let unbatch = |actions: Tensor| if batched { Ok(actions) } else { actions.squeeze(0) };
+
+        let RunMode::Train = mode else {
+            return unbatch(self.actor.forward(&state)?)
+        };
+
+        // the schedule advances once per environment step, i.e. per state
+        let scale = self.noise_schedule.scale();
+        for _ in 0..state.dim(0)? {
+            self.noise_schedule.step();
+        }
+
+        // Noise in parameter space is sampled once per episode
+        if self.perturbed_actor.is_none() {
+            self.perturbed_actor = self
+                .noise
+                .perturb_parameters(&self.actor.parameters(), scale)?
+                .map(Actor::layers_from_parameters);
+        }
+
+        let actions = match &self.perturbed_actor {
+            Some(layers) => Actor::forward_layers(layers, self.actor.encoder.as_ref(), &state)?,
+            None => self.actor.forward(&state)?,
+        };
+        let actions = unbatch(actions)?;
+        self.noise.apply(&actions, scale)
     }
 
     fn train(&mut self) -> Result<()> {
         let (states, actions, rewards, next_states, terminated, _) =
-            match self.replay_buffer.random_batch(self.batch_size)? {
+            match self.replay_sources.random_batch(
+                &mut self.replay_buffer,
+                self.replay_online_ratio,
+                self.batch_size,
+            )? {
                 Some(v) => v,
                 _ => return Ok(()),
             };
@@ -542,13 +873,54 @@ impl OffPolicyAlgorithm for DDPG<'_> {
             reward = reward,
             next_state = next_state,
         );
-        self.replay_buffer
+        if let Err(e) = self
+            .replay_buffer
             .push(state, action, reward, next_state, terminated, truncated)
+        {
+            warn!("Dropped a transition that does not fit the replay buffer: {e}");
+        }
+
+        let is_true = |t: &Tensor| t.to_vec1::<u8>().is_ok_and(|v| v.iter().any(|&x| x > 0));
+        if is_true(terminated) || is_true(truncated) {
+            self.end_episode();
+        }
     }
 
     fn replay_buffer(&self) -> &ReplayBuffer {
         &self.replay_buffer
     }
+
+    fn finish_pretraining(&mut self) {
+        if self.pretrain_replay_ratio > 0.0 {
+            self.retain_buffer_as_source("pretraining", self.pretrain_replay_ratio);
+        }
+    }
+}
+
+impl ParallelAlgorithm for DDPG<'_> {
+    /// The policy is the actor network, including its encoder.
+    fn policy_weights(&self) -> Result<Vec<(String, Tensor)>> {
+        let data = self.actor.varmap.data().lock().unwrap();
+        data.iter()
+            .filter(|(name, _)| name.starts_with("actor-"))
+            // copy, so that the snapshot does not share storage with the variable
+            .map(|(name, var)| Ok((name.clone(), var.as_tensor().to_device(&Device::Cpu)?.copy()?)))
+            .collect()
+    }
+
+    fn set_policy_weights(
+        &mut self,
+        weights: &[(String, Tensor)],
+    ) -> Result<()> {
+        for (name, weight) in weights {
+            self.actor.varmap.set_one(name, weight.to_device(&self.device)?)?;
+        }
+        Ok(())
+    }
+
+    fn end_episode(&mut self) {
+        DDPG::end_episode(self);
+    }
 }
 
 impl SaveableAlgorithm for DDPG<'_> {
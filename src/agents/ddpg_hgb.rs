@@ -15,12 +15,16 @@ use {
             GoalAwareObservation,
         },
         components::{
+            derive_seed,
             ReplayBuffer,
+            GraphMemory,
+            Planner,
             sgm::{
                 DistanceMode,
+                EdgeStats,
+                PlanStats,
                 is_two_consistent,
                 get_edges,
-                add_node_to_graph,
                 edges_to_replenish,
             },
         },
@@ -37,7 +41,6 @@ use {
             StableGraph,
             NodeIndex,
         },
-        algo::astar,
         Directed,
     },
     tracing::info,
@@ -61,13 +64,20 @@ where
     ddpg: DDPG<'a>,
     device: Device,
 
-    sgm: StableGraph<Env::Observation, OrderedFloat<f64>, Directed>,
-    indices: HashMap<Env::Observation, NodeIndex>,
+    memory: GraphMemory<Env::Observation>,
+    planner: Planner,
+    edge_stats: HashMap<(NodeIndex, NodeIndex), EdgeStats>,
     plan: Vec<Env::Observation>,
+    plan_stats: PlanStats,
     goal_obs: Option<Env::Observation>,
     last_waypoint: Option<Env::Observation>,
+    localized: Option<Env::Observation>,
+    heading_to_goal: bool,
+    shortcutting: bool,
+    shortcut_blocked: bool,
     try_counter: usize,
     eps_counter: usize,
+    episode_ended: bool,
 
     dist_mode: DistanceMode,
     sgm_replenish_freq: usize,
@@ -77,6 +87,9 @@ where
     sgm_waypoint_reward: f64,
     sgm_maxdist: f64,
     sgm_tau: f64,
+    sgm_prune_min_attempts: usize,
+    sgm_prune_reliability: f64,
+    sgm_shortcut: bool,
 
     config: DDPG_HGB_Config,
 }
@@ -91,6 +104,13 @@ where
         self.ddpg.new_buffer(buffer_capacity);
     }
 
+    /// Replace the replay buffer, and add its states as candidates to the
+    /// graph memory so the graph can be built from them right away.
+    pub fn set_buffer(&mut self, buffer: ReplayBuffer) {
+        self.ddpg.set_buffer(buffer);
+        self.add_graph_candidates_from_buffer();
+    }
+
     fn distance(
         &self,
         from_state: &<Env::Observation as GoalAwareObservation>::State,
@@ -132,8 +152,8 @@ where
         let mut candidate = None;
         let mut min_distance = f64::INFINITY;
 
-        for s2 in self.sgm.node_indices() {
-            let s2 = self.sgm.node_weight(s2).unwrap();
+        for s2 in self.memory.graph().node_indices() {
+            let s2 = self.memory.graph().node_weight(s2).unwrap();
 
             let distance = self.distance(
                 s2.achieved_goal(),
@@ -151,7 +171,7 @@ where
     }
 
     fn generate_plan(
-        &self,
+        &mut self,
         obs: &Env::Observation,
     ) -> Vec<Env::Observation> {
 
@@ -159,26 +179,198 @@ where
         let goal = self.get_closest(obs.desired_goal());
 
         if let (Some(start), Some(goal)) = (start, goal) {
-            let istart = self.indices.get(&start);
-            let igoal = self.indices.get(&goal);
+            self.plan_stats.localized = true;
+            self.localized = Some(start.clone());
+
+            let istart = self.memory.indices().get(&start).copied();
+            let igoal = self.memory.indices().get(&goal).copied();
 
             if let Some((istart, igoal)) = istart.zip(igoal) {
-                let path = astar(
-                    &self.sgm,
-                    *istart,
-                    |n| n == *igoal,
-                    |e| *e.weight(),
-                    |_| OrderedFloat(0.0),
+                // the true distance is no lower bound on the estimated edge
+                // costs, so A* falls back to Dijkstra in that case
+                let dist_mode = self.dist_mode;
+                let path = self.planner.plan(
+                    self.memory.graph(),
+                    istart,
+                    igoal,
+                    |s1: &Env::Observation, s2: &Env::Observation| {
+                        match dist_mode {
+                            DistanceMode::True => Env::Observation::distance(s1, s2),
+                            DistanceMode::Estimated => 0.0,
+                        }
+                    },
                 );
 
-                if let Some((_, path)) = path {
-                    return path.into_iter().rev().map(|n| self.sgm.node_weight(n).unwrap().clone()).collect()
+                if let Some(path) = path {
+                    if self.plan_stats.plan_length == 0 {
+                        self.plan_stats.plan_length = path.len();
+                    }
+                    return path.into_iter().rev().map(|n| self.memory.graph().node_weight(n).unwrap().clone()).collect()
                 }
             }
         }
         Vec::new()
     }
 
+    pub fn graph_memory(&self) -> &GraphMemory<Env::Observation> {
+        &self.memory
+    }
+
+    /// Add candidate states from any source, e.g. demonstrations, to the
+    /// graph memory.
+    pub fn add_graph_candidates<I>(&mut self, states: I)
+    where
+        I: IntoIterator<Item = Env::Observation>,
+    {
+        let removed = self.memory.extend(states);
+        self.forget_nodes(removed);
+    }
+
+    /// Add the states in the replay buffer as candidate states to the graph
+    /// memory.
+    pub fn add_graph_candidates_from_buffer(&mut self) {
+        let removed = self.memory.extend_from_buffer(self.ddpg.replay_buffer());
+        self.forget_nodes(removed);
+    }
+
+    /// Forget the edge statistics of nodes that were evicted from the graph memory.
+    fn forget_nodes<I>(&mut self, removed: I)
+    where
+        I: IntoIterator<Item = NodeIndex>,
+    {
+        let removed: Vec<NodeIndex> = removed.into_iter().collect();
+        if !removed.is_empty() {
+            info!("Evicted nodes from graph memory: {:?}", removed);
+            self.edge_stats.retain(|(from, to), _| !removed.contains(from) && !removed.contains(to));
+            self.planner.invalidate();
+        }
+    }
+
+    /// The attempt and success counts of the edges that have been tried.
+    pub fn edge_stats(&self) -> &HashMap<(NodeIndex, NodeIndex), EdgeStats> {
+        &self.edge_stats
+    }
+
+    /// Record an attempt to traverse the edge `from -> to` and fold the new
+    /// reliability of the edge into its weight.
+    ///
+    /// Returns whether the edge should be pruned from the graph.
+    fn record_edge_attempt(
+        &mut self,
+        from: &Env::Observation,
+        to: &Env::Observation,
+        success: bool,
+    ) -> bool {
+        let ifrom = self.memory.indices().get(from);
+        let ito = self.memory.indices().get(to);
+
+        let Some((ifrom, ito)) = ifrom.copied().zip(ito.copied()) else {
+            return false
+        };
+        let Some(edge) = self.memory.graph().find_edge(ifrom, ito) else {
+            return false
+        };
+
+        let stats = self
+            .edge_stats
+            .entry((ifrom, ito))
+            .or_insert_with(|| EdgeStats::new(*self.memory.graph()[edge]));
+        stats.record(success);
+        let stats = *stats;
+
+        let previous_cost = *self.memory.graph()[edge];
+        self.memory.graph_mut()[edge] = OrderedFloat(stats.expected_cost());
+        if stats.expected_cost() <= previous_cost {
+            self.planner.edge_added(ifrom, ito, stats.expected_cost());
+        } else {
+            self.planner.invalidate();
+        }
+
+        info!(
+            "Edge stats {:#?} -> {:#?}: {:#?} (reliability {:.3})",
+            from,
+            to,
+            stats,
+            stats.reliability(),
+        );
+
+        stats.is_unreliable(self.sgm_prune_min_attempts, self.sgm_prune_reliability)
+    }
+
+    /// Skip ahead in the plan when the goal or a later waypoint is close.
+    ///
+    /// If the goal is directly reachable, i.e. within `sgm_maxdist`, the plan
+    /// is dropped and the agent heads for the goal. Otherwise the plan is cut
+    /// off at the furthest waypoint that is within `sgm_close_enough`, which
+    /// then counts as reached, or within `sgm_maxdist`, which is aimed for
+    /// next. With [`DistanceMode::Estimated`] the distances are estimated by
+    /// the critic, which accounts for walls in between.
+    ///
+    /// Skipping to a waypoint traverses an edge from the node the agent was
+    /// last localized at, which is added to the graph if needed, so a failing
+    /// shortcut is recorded and pruned like any other edge. After a failed
+    /// shortcut, or failing to reach the goal directly, the plan is followed
+    /// without shortcuts for the rest of the episode.
+    fn shortcut_plan(
+        &mut self,
+        obs: &Env::Observation,
+    ) {
+        let distance_to_goal = self.distance(
+            obs.achieved_goal(),
+            obs.desired_goal(),
+            obs.observation(),
+        );
+        if distance_to_goal <= self.sgm_maxdist {
+            info!("Goal is directly reachable, skipping {} waypoints", self.plan.len());
+            self.plan = Vec::new();
+            self.heading_to_goal = true;
+            self.shortcutting = true;
+            self.try_counter = 0;
+            return
+        }
+
+        // the plan is a stack, so the furthest waypoint comes first and the
+        // next waypoint comes last, which would be no skip
+        let skippable = self.plan.len().saturating_sub(1);
+        let furthest = self.plan[..skippable]
+            .iter()
+            .map(|waypoint| self.distance(obs.achieved_goal(), waypoint.achieved_goal(), obs.observation()))
+            .enumerate()
+            .find(|(_, distance)| *distance <= self.sgm_maxdist);
+        let Some((idx, distance)) = furthest else {
+            return
+        };
+        let skipped = self.plan.len() - (idx + 1);
+
+        if distance <= self.sgm_close_enough {
+            info!("Waypoint is close enough, skipping {} waypoints", skipped);
+            self.plan.truncate(idx + 1);
+            self.last_waypoint = self.plan.pop();
+            self.localized = self.last_waypoint.clone();
+            self.try_counter = 0;
+            return
+        }
+
+        let Some(localized) = self.localized.clone() else {
+            return
+        };
+        let ifrom = self.memory.indices().get(&localized).copied();
+        let ito = self.memory.indices().get(&self.plan[idx]).copied();
+        let Some((ifrom, ito)) = ifrom.zip(ito).filter(|(ifrom, ito)| ifrom != ito) else {
+            return
+        };
+        if self.memory.graph().find_edge(ifrom, ito).is_none() {
+            self.memory.graph_mut().add_edge(ifrom, ito, OrderedFloat(distance));
+            self.planner.edge_added(ifrom, ito, distance);
+        }
+
+        info!("Waypoint is directly reachable, skipping {} waypoints", skipped);
+        self.plan.truncate(idx + 1);
+        self.last_waypoint = Some(localized);
+        self.shortcutting = true;
+        self.try_counter = 0;
+    }
+
     fn splice_state_as_goal_into_obs(
         &self,
         obs: &Env::Observation,
@@ -207,13 +399,27 @@ where
             ddpg,
             device: device.clone(),
 
-            sgm: StableGraph::default(),
-            indices: HashMap::new(),
+            memory: GraphMemory::new(
+                config.sgm_memory_capacity,
+                config.sgm_memory_eviction,
+            ),
+            planner: Planner::new(
+                config.sgm_planner,
+                config.sgm_max_plan_length,
+                config.sgm_max_edge_cost,
+            ),
+            edge_stats: HashMap::new(),
             plan: Vec::new(),
+            plan_stats: PlanStats::default(),
             goal_obs: None,
             last_waypoint: None,
+            localized: None,
+            heading_to_goal: false,
+            shortcutting: false,
+            shortcut_blocked: false,
             try_counter: 0,
             eps_counter: 0,
+            episode_ended: false,
 
             dist_mode: config.distance_mode,
             sgm_replenish_freq: config.sgm_replenish_freq,
@@ -223,6 +429,9 @@ where
             sgm_waypoint_reward: config.sgm_waypoint_reward,
             sgm_maxdist: config.sgm_maxdist,
             sgm_tau: config.sgm_tau,
+            sgm_prune_min_attempts: config.sgm_prune_min_attempts,
+            sgm_prune_reliability: config.sgm_prune_reliability,
+            sgm_shortcut: config.sgm_shortcut,
 
             config: config.clone(),
         }))
@@ -252,6 +461,19 @@ where
         self.sgm_waypoint_reward = config.sgm_waypoint_reward;
         self.sgm_maxdist = config.sgm_maxdist;
         self.sgm_tau = config.sgm_tau;
+        self.sgm_prune_min_attempts = config.sgm_prune_min_attempts;
+        self.sgm_prune_reliability = config.sgm_prune_reliability;
+        self.sgm_shortcut = config.sgm_shortcut;
+        let removed = self.memory.set_capacity(
+            config.sgm_memory_capacity,
+            config.sgm_memory_eviction,
+        );
+        self.forget_nodes(removed);
+        self.planner = Planner::new(
+            config.sgm_planner,
+            config.sgm_max_plan_length,
+            config.sgm_max_edge_cost,
+        );
 
         self.config.distance_mode = config.distance_mode;
         self.config.sgm_replenish_freq = config.sgm_replenish_freq;
@@ -261,6 +483,14 @@ where
         self.config.sgm_waypoint_reward = config.sgm_waypoint_reward;
         self.config.sgm_maxdist = config.sgm_maxdist;
         self.config.sgm_tau = config.sgm_tau;
+        self.config.sgm_planner = config.sgm_planner;
+        self.config.sgm_max_plan_length = config.sgm_max_plan_length;
+        self.config.sgm_max_edge_cost = config.sgm_max_edge_cost;
+        self.config.sgm_prune_min_attempts = config.sgm_prune_min_attempts;
+        self.config.sgm_prune_reliability = config.sgm_prune_reliability;
+        self.config.sgm_shortcut = config.sgm_shortcut;
+        self.config.sgm_memory_capacity = config.sgm_memory_capacity;
+        self.config.sgm_memory_eviction = config.sgm_memory_eviction;
 
         self.ddpg.override_config(&config.ddpg);
         self.config.ddpg = self.ddpg.config().clone();
@@ -276,14 +506,28 @@ where
             ddpg: *DDPG::from_config(device, &config.ddpg, size_state, size_action)?,
             device: device.clone(),
 
-            sgm: StableGraph::default(),
-            indices: HashMap::new(),
+            memory: GraphMemory::new(
+                config.sgm_memory_capacity,
+                config.sgm_memory_eviction,
+            ),
+            planner: Planner::new(
+                config.sgm_planner,
+                config.sgm_max_plan_length,
+                config.sgm_max_edge_cost,
+            ),
+            edge_stats: HashMap::new(),
             plan: Vec::new(),
+            plan_stats: PlanStats::default(),
             try_counter: 0,
             eps_counter: 0,
+            episode_ended: false,
 
             goal_obs: None,
             last_waypoint: None,
+            localized: None,
+            heading_to_goal: false,
+            shortcutting: false,
+            shortcut_blocked: false,
 
             dist_mode: config.distance_mode,
             sgm_replenish_freq: config.sgm_replenish_freq,
@@ -293,11 +537,23 @@ where
             sgm_waypoint_reward: config.sgm_waypoint_reward,
             sgm_maxdist: config.sgm_maxdist,
             sgm_tau: config.sgm_tau,
+            sgm_prune_min_attempts: config.sgm_prune_min_attempts,
+            sgm_prune_reliability: config.sgm_prune_reliability,
+            sgm_shortcut: config.sgm_shortcut,
 
             config: config.clone(),
         }))
     }
 
+    fn seed(
+        &mut self,
+        seed: u64,
+    ) -> Result<()> {
+        self.ddpg.seed(seed)?;
+        self.memory.seed(derive_seed(seed, "graph_memory"));
+        Ok(())
+    }
+
     fn actions(
         &mut self,
         state: &Tensor,
@@ -322,11 +578,19 @@ where
         //     }
         // }
 
-        // IF the goal has changed (we are in a new episode) AND we are in RunMode::Train
+        // A new episode starts after a remembered terminal transition, or when
+        // the goal has changed (e.g. in evaluation, where nothing is remembered)
+        let new_episode = self.episode_ended || self
+            .goal_obs
+            .as_ref()
+            .is_some_and(|goal_obs| curr_obs.desired_goal() != goal_obs.desired_goal());
+        self.episode_ended = false;
+
+        // IF we are in a new episode AND we are in RunMode::Train
         //      reconstruct the graph every n episodes
 
         if let RunMode::Train = mode {
-            if self.goal_obs.is_some() && curr_obs.desired_goal() != self.goal_obs.as_ref().unwrap().desired_goal() {
+            if self.goal_obs.is_some() && new_episode {
                 self.eps_counter += 1;
                 if (self.sgm_replenish_freq > 0) && (self.eps_counter % self.sgm_replenish_freq == 0) {
                     info!("Replenishing graph");
@@ -339,19 +603,25 @@ where
             }
         }
 
-        // IF the goal has changed OR we have no goal
-        //      forget the plan
+        // IF we are in a new episode OR we have no goal
+        //      forget the plan and its stats
         //      set the new goal
 
-        if self.goal_obs.is_none() || curr_obs.desired_goal() != self.goal_obs.as_ref().unwrap().desired_goal() {
+        if self.goal_obs.is_none() || new_episode {
             self.plan = Vec::new();
+            self.plan_stats = PlanStats::default();
             self.goal_obs = Some(curr_obs.clone());
             self.last_waypoint = None;
+            self.localized = None;
+            self.heading_to_goal = false;
+            self.shortcutting = false;
+            self.shortcut_blocked = false;
+            self.try_counter = 0;
         }
 
         // try adding curr_obs to the graph
         if is_two_consistent(
-            &self.sgm,
+            self.memory.graph(),
             &curr_obs,
             |s1: &Env::Observation, s2: &Env::Observation| {
                 self.distance(
@@ -363,8 +633,8 @@ where
             self.sgm_tau,
         ) {
             let (edges_from, edges_to) = get_edges(
-                &self.sgm,
-                &self.indices,
+                self.memory.graph(),
+                self.memory.indices(),
                 &curr_obs,
                 |s1: &Env::Observation, s2: &Env::Observation| {
                     self.distance(
@@ -376,51 +646,82 @@ where
                 self.sgm_maxdist,
             );
 
-            add_node_to_graph(
-                &mut self.sgm,
-                &mut self.indices,
+            let removed = self.memory.add_node(
                 &curr_obs,
                 edges_from,
                 edges_to,
             );
+            self.forget_nodes(removed);
+            if let Some(node) = self.memory.indices().get(&curr_obs).copied() {
+                self.planner.node_added(self.memory.graph(), node);
+                self.localized = Some(curr_obs.clone());
+            }
             info!("Added node to graph: {:#?}", curr_obs);
         }
 
         // IF we have a plan AND we have been trying too long
-        //      remove the edge
+        //      record the failed attempt
+        //      remove the edge if it is unreliable
         //      forget the plan
 
         if !self.plan.is_empty() && self.try_counter > self.sgm_max_tries {
             if let Some(last_waypoint) = self.last_waypoint.clone() {
 
                 let a = &last_waypoint;
-                let b = self.plan.last().unwrap();
+                let b = &self.plan.last().unwrap().clone();
 
-                info!("Removing edges: {:#?} <-> {:#?}", a, b);
+                if self.record_edge_attempt(a, b, false) {
+                    info!("Removing edges: {:#?} <-> {:#?}", a, b);
+                    self.plan_stats.edge_removals += 1;
 
-                for (from, to) in [(a, b), (b, a)] {
-                    let ia = self.indices.get(from);
-                    let ib = self.indices.get(to);
+                    for (from, to) in [(a, b), (b, a)] {
+                        let ia = self.memory.indices().get(from).copied();
+                        let ib = self.memory.indices().get(to).copied();
 
-                    if let Some((ia, ib)) = ia.zip(ib) {
-                        if let Some(edge) = self.sgm.find_edge(*ia, *ib) {
-                            self.sgm.remove_edge(edge);
+                        if let Some((ia, ib)) = ia.zip(ib) {
+                            if let Some(edge) = self.memory.graph().find_edge(ia, ib) {
+                                self.memory.graph_mut().remove_edge(edge);
+                            }
+                            self.edge_stats.remove(&(ia, ib));
                         }
                     }
+                    self.planner.invalidate();
                 }
             }
 
             self.plan = Vec::new();
             self.try_counter = 0;
+            if self.shortcutting {
+                self.shortcutting = false;
+                self.shortcut_blocked = true;
+            }
+        }
+
+        // IF we are heading for the goal directly AND we have been trying too long
+        //      plan again, without shortcuts for the rest of the episode
+
+        if self.heading_to_goal && self.try_counter > self.sgm_max_tries {
+            info!("Goal was not reached directly, planning again");
+            self.heading_to_goal = false;
+            self.shortcutting = false;
+            self.shortcut_blocked = true;
+            self.try_counter = 0;
         }
 
-        // IF we dont have a plan
+        // IF we dont have a plan AND we are not heading for the goal directly
         //      try making a plan to the goal
 
-        if self.plan.is_empty() {
+        if self.plan.is_empty() && !self.heading_to_goal {
             self.plan = self.generate_plan(&curr_obs);
         }
 
+        // IF we have a plan AND shortcutting is enabled AND no shortcut failed
+        //      skip ahead to the goal or the furthest close waypoint
+
+        if !self.plan.is_empty() && self.sgm_shortcut && !self.shortcut_blocked {
+            self.shortcut_plan(&curr_obs);
+        }
+
         // IF we have a plan
         //      try reaching the next waypoint
         // ELSE
@@ -460,6 +761,22 @@ where
         terminated: &Tensor,
         truncated: &Tensor,
     ) {
+        // The next call to actions starts a new episode, even if its goal is
+        // the same as in this one
+        if self.tensor_is_true(terminated) || self.tensor_is_true(truncated) {
+            self.episode_ended = true;
+        }
+
+        // Every state we encounter is a candidate for the graph memory
+        self.add_graph_candidates([
+            <Env::Observation>::from_tensor(state.clone()),
+            <Env::Observation>::from_tensor(next_state.clone()),
+        ]);
+
+        if self.plan.is_empty() && self.heading_to_goal {
+            self.try_counter += 1;
+        }
+
         // If the plan is empty, we default to the DDPG policy
         if self.plan.is_empty() || self.tensor_is_true(terminated) || self.tensor_is_true(truncated) { // || terminated || truncated
             self.ddpg.remember(
@@ -502,7 +819,13 @@ where
             // - Reset the try counter
             // - Pretend we got a reward from the environment for reaching the waypoint
             if distance_to_waypoint <= self.sgm_close_enough {
-                self.last_waypoint = self.plan.pop();
+                let waypoint = self.plan.pop();
+                if let Some((from, to)) = self.last_waypoint.clone().zip(waypoint.clone()) {
+                    self.record_edge_attempt(&from, &to, true);
+                }
+                self.localized = waypoint.clone();
+                self.last_waypoint = waypoint;
+                self.shortcutting = false;
                 self.try_counter = 0;
                 reward = Tensor::new(vec![self.sgm_waypoint_reward], &self.device).unwrap();
             } else {
@@ -524,6 +847,10 @@ where
     fn replay_buffer(&self) -> &ReplayBuffer {
         self.ddpg.replay_buffer()
     }
+
+    fn finish_pretraining(&mut self) {
+        self.ddpg.finish_pretraining();
+    }
 }
 
 
@@ -537,19 +864,24 @@ where
         &self.plan
     }
 
+    fn plan_stats(&self) -> &PlanStats {
+        &self.plan_stats
+    }
+
     fn graph(&self) -> &StableGraph<Env::Observation, OrderedFloat<f64>, Directed> {
-        &self.sgm
+        self.memory.graph()
     }
 
     fn clear_graph(&mut self) {
-        self.sgm = StableGraph::default();
-        self.indices = HashMap::new();
+        self.memory.clear_graph();
+        self.edge_stats = HashMap::new();
+        self.planner.invalidate();
     }
 
     fn construct_graph(&mut self) {
-        (self.sgm, self.indices) = self
-            .replay_buffer()
-            .construct_sgm(
+        self.memory = self
+            .memory
+            .constructed(
                 |s1: &Env::Observation, s2: &Env::Observation| {
                     self.distance(
                         s1.achieved_goal(),
@@ -560,12 +892,14 @@ where
                 self.sgm_maxdist,
                 self.sgm_tau,
             );
+        self.edge_stats = HashMap::new();
+        self.planner.invalidate();
     }
 
     fn replenish_graph(&mut self) {
         let edges_to_replenish = edges_to_replenish(
-            &self.sgm,
-            &self.indices,
+            self.memory.graph(),
+            self.memory.indices(),
             |s1: &Env::Observation, s2: &Env::Observation| {
                 self.distance(
                     s1.achieved_goal(),
@@ -577,7 +911,8 @@ where
         );
 
         for (i1, i2, weight) in edges_to_replenish {
-            self.sgm.add_edge(i1, i2, weight);
+            self.memory.graph_mut().add_edge(i1, i2, weight);
+            self.planner.edge_added(i1, i2, *weight);
         }
     }
 }
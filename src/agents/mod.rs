@@ -9,7 +9,10 @@ use {
     crate::{
         engines::RunMode,
         envs::Environment,
-        components::ReplayBuffer,
+        components::{
+            ReplayBuffer,
+            sgm::PlanStats,
+        },
     },
     ordered_float::OrderedFloat,
     petgraph::{
@@ -54,6 +57,13 @@ pub trait Algorithm {
         size_action: usize,
     ) -> Result<Box<Self>>;
 
+    /// Seed all sources of randomness of the algorithm, including the
+    /// initialization of its networks, which are re-initialized.
+    fn seed(
+        &mut self,
+        seed: u64,
+    ) -> Result<()>;
+
     fn actions(
         &mut self,
         state: &Tensor,
@@ -75,10 +85,34 @@ pub trait OffPolicyAlgorithm: Algorithm {
     );
 
     fn replay_buffer(&self) -> &ReplayBuffer;
+
+    /// Called once after pretraining, before training on the target
+    /// environment, e.g. to keep the pretraining data as a separate replay
+    /// source.
+    fn finish_pretraining(&mut self);
+}
+
+/// An off-policy algorithm whose acting policy can be copied into other
+/// instances, e.g. the rollout workers of
+/// [`loop_off_policy_parallel`](crate::engines::loop_off_policy_parallel).
+pub trait ParallelAlgorithm: OffPolicyAlgorithm {
+    /// A copy of the weights of the policy on the CPU, by variable name.
+    fn policy_weights(&self) -> Result<Vec<(String, Tensor)>>;
+
+    /// Overwrite the weights of the policy with ones from [`ParallelAlgorithm::policy_weights`].
+    fn set_policy_weights(
+        &mut self,
+        weights: &[(String, Tensor)],
+    ) -> Result<()>;
+
+    /// Called at the end of every episode that the algorithm acts in without
+    /// remembering its transitions, e.g. to reset the exploration noise.
+    fn end_episode(&mut self);
 }
 
 pub trait HgbAlgorithm<Env: Environment>: Algorithm {
     fn plan(&self) -> &Vec<Env::Observation>;
+    fn plan_stats(&self) -> &PlanStats;
     fn graph(&self) -> &StableGraph<Env::Observation, OrderedFloat<f64>, Directed>;
     fn clear_graph(&mut self);
     fn construct_graph(&mut self);
@@ -0,0 +1,140 @@
+use {
+    crate::configs::EncoderConfig,
+    candle_core::{
+        Module,
+        Result,
+        Tensor,
+    },
+    candle_nn::{
+        conv2d,
+        linear,
+        Conv2d,
+        Conv2dConfig,
+        Linear,
+        VarBuilder,
+    },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    strum::EnumIter,
+    std::fmt::Display,
+};
+
+/// The kind of encoder that is put in front of the actor and critic networks.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, EnumIter)]
+pub enum EncoderKind {
+    /// The observation vector is fed to the networks directly.
+    None,
+    /// The trailing pixels of the observation are encoded by a [ConvEncoder].
+    Conv,
+}
+impl Display for EncoderKind {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Conv => write!(f, "Conv"),
+        }
+    }
+}
+
+/// A convolutional encoder for image observations.
+///
+/// The observation vector is expected to end with an image of `image_channels`
+/// x `image_size` x `image_size` pixels (channels first, row-major). Any values
+/// in front of the image are passed on next to the image features if
+/// `include_state` is set, and are dropped otherwise.
+///
+/// The image goes through two 3x3 convolutions with stride 2 and a linear
+/// layer, all with ReLU activations, into `feature_size` features.
+#[derive(Clone)]
+pub struct ConvEncoder {
+    convs: Vec<Conv2d>,
+    fc: Linear,
+    leading: usize,
+    image_channels: usize,
+    image_size: usize,
+    include_state: bool,
+}
+impl ConvEncoder {
+    /// Create a new [ConvEncoder] for observations of size `size_state`.
+    pub fn new(
+        vb: VarBuilder,
+        config: &EncoderConfig,
+        size_state: usize,
+    ) -> Result<Self> {
+        let n_pixels = config.image_channels * config.image_size.pow(2);
+        if config.image_size == 0 || n_pixels > size_state {
+            candle_core::bail!(
+                "An image of {}x{}x{} pixels does not fit into observations of size {size_state}",
+                config.image_channels,
+                config.image_size,
+                config.image_size,
+            );
+        }
+        let conv_config = Conv2dConfig {
+            padding: 1,
+            stride: 2,
+            ..Default::default()
+        };
+        let convs = vec![
+            conv2d(config.image_channels, config.conv_channels, 3, conv_config, vb.pp("conv0"))?,
+            conv2d(config.conv_channels, config.conv_channels, 3, conv_config, vb.pp("conv1"))?,
+        ];
+        // every convolution halves the image size (rounding up)
+        let conv_size = config.image_size.div_ceil(2).div_ceil(2);
+        let fc = linear(
+            config.conv_channels * conv_size.pow(2),
+            config.feature_size,
+            vb.pp("fc"),
+        )?;
+        Ok(Self {
+            convs,
+            fc,
+            leading: size_state - n_pixels,
+            image_channels: config.image_channels,
+            image_size: config.image_size,
+            include_state: config.include_state,
+        })
+    }
+
+    /// The number of features the encoder produces for observations of size
+    /// `size_state`, i.e. the input size of the networks behind it.
+    pub fn output_size(
+        config: &EncoderConfig,
+        size_state: usize,
+    ) -> usize {
+        let n_pixels = config.image_channels * config.image_size.pow(2);
+        if config.include_state {
+            config.feature_size + size_state.saturating_sub(n_pixels)
+        } else {
+            config.feature_size
+        }
+    }
+}
+impl Module for ConvEncoder {
+    fn forward(
+        &self,
+        xs: &Tensor,
+    ) -> Result<Tensor> {
+        let batch_size = xs.dim(0)?;
+        let n_pixels = self.image_channels * self.image_size.pow(2);
+
+        let mut image = xs
+            .narrow(1, self.leading, n_pixels)?
+            .reshape((batch_size, self.image_channels, self.image_size, self.image_size))?;
+        for conv in self.convs.iter() {
+            image = conv.forward(&image)?.relu()?;
+        }
+        let features = self.fc.forward(&image.flatten_from(1)?)?.relu()?;
+
+        if self.include_state && self.leading > 0 {
+            Tensor::cat(&[&xs.narrow(1, 0, self.leading)?, &features], 1)
+        } else {
+            Ok(features)
+        }
+    }
+}
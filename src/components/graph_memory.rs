@@ -0,0 +1,346 @@
+use {
+    super::{
+        ReplayBuffer,
+        sgm::{
+            add_node_to_graph,
+            construct_sgm_from_states,
+        },
+    },
+    crate::envs::TensorConvertible,
+    ordered_float::OrderedFloat,
+    petgraph::{
+        stable_graph::{
+            NodeIndex,
+            StableGraph,
+        },
+        Directed,
+    },
+    rand::{
+        rngs::StdRng,
+        Rng,
+        SeedableRng,
+    },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    strum::EnumIter,
+    std::{
+        collections::{
+            HashMap,
+            VecDeque,
+        },
+        fmt::Display,
+        hash::Hash,
+    },
+};
+
+
+/// Which state to evict from a full [`GraphMemory`].
+///
+/// States that are not nodes in the graph are always evicted before states
+/// that are, so the graph only shrinks once the memory consists of nothing
+/// but graph nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum EvictionPolicy {
+    Fifo,
+    Random,
+}
+
+impl Display for EvictionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvictionPolicy::Fifo => write!(f, "Fifo"),
+            EvictionPolicy::Random => write!(f, "Random"),
+        }
+    }
+}
+
+/// A bounded store of candidate states and the sparse graph built on them.
+///
+/// The memory is independent of any [`ReplayBuffer`], so candidate states can
+/// come from any source (the environment stream, a replay buffer,
+/// demonstrations, ...) and the graph survives when the buffer is replaced.
+///
+/// Nodes are only ever removed from the graph one at a time with
+/// [`StableGraph::remove_node`], so the [`NodeIndex`] of the remaining nodes
+/// stays valid.
+///
+/// # Fields
+///
+/// * `members` - The candidate states, with the order they were added in.
+/// * `candidates` - The queue of candidate states that are not graph nodes.
+///   Entries of states that were evicted or became graph nodes since are
+///   skipped when they come up.
+/// * `nodes` - The queue of graph nodes, in the order they were added.
+/// * `next_order` - The order of the next added state.
+/// * `capacity` - The maximum number of candidate states (0 means unbounded).
+/// * `policy` - The [`EvictionPolicy`] used when the memory is full.
+/// * `graph` - The graph built on (a subset of) the candidate states.
+/// * `indices` - Maps the states in the graph to their [`NodeIndex`].
+/// * `rng` - The random number generator used by [`EvictionPolicy::Random`].
+#[derive(Clone)]
+pub struct GraphMemory<S>
+where
+    S: Clone + Eq + Hash,
+{
+    members: HashMap<S, u64>,
+    candidates: VecDeque<(u64, S)>,
+    nodes: VecDeque<NodeIndex>,
+    next_order: u64,
+    capacity: usize,
+    policy: EvictionPolicy,
+    graph: StableGraph<S, OrderedFloat<f64>, Directed>,
+    indices: HashMap<S, NodeIndex>,
+    rng: StdRng,
+}
+impl<S> GraphMemory<S>
+where
+    S: Clone + Eq + Hash,
+{
+    /// Create a new, empty graph memory.
+    pub fn new(
+        capacity: usize,
+        policy: EvictionPolicy,
+    ) -> Self {
+        Self {
+            members: HashMap::new(),
+            candidates: VecDeque::new(),
+            nodes: VecDeque::new(),
+            next_order: 0,
+            capacity,
+            policy,
+            graph: StableGraph::default(),
+            indices: HashMap::new(),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Seed the random number generator used for random eviction.
+    pub fn seed(
+        &mut self,
+        seed: u64,
+    ) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Get the number of candidate states.
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Get the candidate states, in the order they were added.
+    pub fn states(&self) -> Vec<S> {
+        let mut states: Vec<(&u64, &S)> = self.members.iter().map(|(s, order)| (order, s)).collect();
+        states.sort_unstable_by_key(|(order, _)| **order);
+        states.into_iter().map(|(_, s)| s.clone()).collect()
+    }
+
+    pub fn graph(&self) -> &StableGraph<S, OrderedFloat<f64>, Directed> {
+        &self.graph
+    }
+
+    /// Get mutable access to the graph to edit its edges.
+    ///
+    /// Nodes must be added and removed through the [`GraphMemory`] itself,
+    /// otherwise the `indices` get out of sync with the graph.
+    pub fn graph_mut(&mut self) -> &mut StableGraph<S, OrderedFloat<f64>, Directed> {
+        &mut self.graph
+    }
+
+    pub fn indices(&self) -> &HashMap<S, NodeIndex> {
+        &self.indices
+    }
+
+    /// Set the capacity and eviction policy, evicting states if necessary.
+    ///
+    /// Returns the nodes that were removed from the graph.
+    pub fn set_capacity(
+        &mut self,
+        capacity: usize,
+        policy: EvictionPolicy,
+    ) -> Vec<NodeIndex> {
+        self.capacity = capacity;
+        self.policy = policy;
+
+        let mut removed = Vec::new();
+        while self.capacity > 0 && self.members.len() > self.capacity {
+            let size = self.members.len();
+            removed.extend(self.evict());
+            if self.members.len() == size {
+                break;
+            }
+        }
+        removed
+    }
+
+    /// Add a candidate state to the memory, evicting one if it is full.
+    ///
+    /// Returns the node that was removed from the graph, if any.
+    pub fn push(
+        &mut self,
+        state: S,
+    ) -> Option<NodeIndex> {
+        if self.members.contains_key(&state) {
+            return None;
+        }
+        let removed = if self.capacity > 0 && self.members.len() >= self.capacity {
+            self.evict()
+        } else {
+            None
+        };
+        let order = self.next_order;
+        self.next_order += 1;
+        match self.indices.get(&state) {
+            Some(node) => self.nodes.push_back(*node),
+            None => self.candidates.push_back((order, state.clone())),
+        }
+        self.members.insert(state, order);
+        removed
+    }
+
+    /// Add many candidate states to the memory.
+    ///
+    /// Returns the nodes that were removed from the graph.
+    pub fn extend<I>(
+        &mut self,
+        states: I,
+    ) -> Vec<NodeIndex>
+    where
+        I: IntoIterator<Item = S>,
+    {
+        states.into_iter().filter_map(|s| self.push(s)).collect()
+    }
+
+    /// Add the states in the replay buffer as candidate states.
+    ///
+    /// Returns the nodes that were removed from the graph.
+    pub fn extend_from_buffer(
+        &mut self,
+        buffer: &ReplayBuffer,
+    ) -> Vec<NodeIndex>
+    where
+        S: TensorConvertible,
+    {
+        self.extend(buffer.all_states::<S>())
+    }
+
+    /// Add a state and its edges to the graph.
+    ///
+    /// The state is also added as a candidate state if it is not one already.
+    pub fn add_node(
+        &mut self,
+        s1: &S,
+        edges_from: Vec<(NodeIndex, OrderedFloat<f64>)>,
+        edges_to: Vec<(NodeIndex, OrderedFloat<f64>)>,
+    ) -> Option<NodeIndex> {
+        add_node_to_graph(&mut self.graph, &mut self.indices, s1, edges_from, edges_to);
+        if self.members.contains_key(s1) {
+            // its entry in the candidates queue is skipped from now on
+            self.nodes.push_back(self.indices[s1]);
+            None
+        } else {
+            // the new node is queued after the eviction, so it cannot evict
+            // itself, but once the memory holds nothing but graph nodes the
+            // eviction can remove one of its neighbours along with their edges
+            self.push(s1.clone())
+        }
+    }
+
+    /// Remove all nodes from the graph, but keep the candidate states.
+    pub fn clear_graph(&mut self) {
+        self.graph = StableGraph::default();
+        self.indices = HashMap::new();
+        self.rebuild_queues();
+    }
+
+    /// Return a copy of this memory with the graph rebuilt from the candidate
+    /// states.
+    ///
+    /// This does not rebuild the graph in place, so that the distance function
+    /// can borrow whatever owns the memory. For more details, see
+    /// [`ReplayBuffer::construct_sgm`].
+    pub fn constructed<D>(
+        &self,
+        d: D,
+        maxdist: f64,
+        tau: f64,
+    ) -> Self
+    where
+        D: Fn(&S, &S) -> f64,
+    {
+        let (graph, indices) = construct_sgm_from_states(&self.states(), d, maxdist, tau);
+
+        let mut memory = Self {
+            members: self.members.clone(),
+            candidates: VecDeque::new(),
+            nodes: VecDeque::new(),
+            next_order: self.next_order,
+            capacity: self.capacity,
+            policy: self.policy,
+            graph,
+            indices,
+            rng: self.rng.clone(),
+        };
+        memory.rebuild_queues();
+        memory
+    }
+
+    /// Rebuild both eviction queues from the candidate states, after the
+    /// graph was replaced.
+    fn rebuild_queues(&mut self) {
+        let mut states: Vec<(u64, &S)> = self.members.iter().map(|(s, order)| (*order, s)).collect();
+        states.sort_unstable_by_key(|(order, _)| *order);
+
+        self.candidates = VecDeque::new();
+        self.nodes = VecDeque::new();
+        for (order, state) in states {
+            match self.indices.get(state) {
+                Some(node) => self.nodes.push_back(*node),
+                None => self.candidates.push_back((order, state.clone())),
+            }
+        }
+    }
+
+    /// Take an entry from an eviction queue according to the eviction policy.
+    fn take<T>(
+        queue: &mut VecDeque<T>,
+        policy: EvictionPolicy,
+        rng: &mut StdRng,
+    ) -> Option<T> {
+        match policy {
+            EvictionPolicy::Fifo => queue.pop_front(),
+            EvictionPolicy::Random => {
+                if queue.is_empty() {
+                    None
+                } else {
+                    let position = rng.gen_range(0..queue.len());
+                    queue.swap_remove_back(position)
+                }
+            },
+        }
+    }
+
+    /// Evict a single candidate state according to the eviction policy.
+    ///
+    /// Returns the node that was removed from the graph, if any.
+    fn evict(&mut self) -> Option<NodeIndex> {
+        while let Some((order, state)) = Self::take(&mut self.candidates, self.policy, &mut self.rng) {
+            let current = self.members.get(&state) == Some(&order);
+            if current && !self.indices.contains_key(&state) {
+                self.members.remove(&state);
+                return None;
+            }
+        }
+
+        // entries of nodes that are no longer in the graph are skipped
+        while let Some(node) = Self::take(&mut self.nodes, self.policy, &mut self.rng) {
+            if let Some(state) = self.graph.remove_node(node) {
+                self.indices.remove(&state);
+                self.members.remove(&state);
+                return Some(node);
+            }
+        }
+        None
+    }
+}
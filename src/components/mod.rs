@@ -5,23 +5,79 @@
 //! ## Noise
 //!
 //! The `Noise` components are typically used to add noise to the actions of an
-//! agent. For example, the [`OuNoise`] struct implements the Ornstein-Uhlenbeck
-//! process, which is typically used in the [`crate::agents::DDPG`] algorithm.
+//! agent. They implement the [`ExplorationNoise`] trait, and are scaled down
+//! over time by a [`NoiseSchedule`]. For example, the [`OuNoise`] struct
+//! implements the Ornstein-Uhlenbeck process, which is typically used in the
+//! [`crate::agents::DDPG`] algorithm.
 //!
 //! ## Replay Buffer
 //!
 //! The [`ReplayBuffer`] struct implements a replay buffer, which is typically
-//! used in off-policy algorithms such as [`crate::agents::DDPG`].
+//! used in off-policy algorithms such as [`crate::agents::DDPG`]. Additional
+//! buffers, e.g. with pretraining data or demonstrations, can be mixed into the
+//! training batches with a [`ReplayMixture`].
 //!
 //! ## SGM
 //!
 //! The [`sgm`] module implements Sparse Graphical Memory, which is used in the
 //! [`crate::agents::DDPG_SGM`] algorithm to build a sparse graph on top of the
 //! replay buffer.
+//!
+//! ## Graph Memory
+//!
+//! The [`GraphMemory`] struct stores candidate states and the sparse graph
+//! built on them, independently of the [`ReplayBuffer`].
+//!
+//! ## Planner
+//!
+//! The [`Planner`] struct finds paths through the graph built by the [`sgm`]
+//! module, using one of the search strategies given by [`PlannerMode`].
+//!
+//! ## Encoder
+//!
+//! The [`ConvEncoder`] struct encodes image observations into features for the
+//! actor and critic networks, if selected by the [`EncoderKind`].
+//!
+//! ## Seeding
+//!
+//! The [`derive_seed`] function derives independent seeds for every source of
+//! randomness from a single master seed, so that whole runs are reproducible.
 
 mod noise;
 mod replay_buffer;
+mod replay_mixture;
+mod planner;
+mod graph_memory;
+mod seeding;
+mod encoder;
 
 pub mod sgm;
-pub use noise::OuNoise;
+pub use noise::{
+    ExplorationNoise,
+    NoiseSchedule,
+    NoiseKind,
+    NoiseDecay,
+    ScheduleUnit,
+    OuNoise,
+    GaussianNoise,
+    EpsilonRandomNoise,
+    ParameterNoise,
+};
 pub use replay_buffer::ReplayBuffer;
+pub use replay_mixture::{
+    ReplayMixture,
+    ReplaySource,
+};
+pub use graph_memory::{
+    GraphMemory,
+    EvictionPolicy,
+};
+pub use planner::{
+    Planner,
+    PlannerMode,
+};
+pub use seeding::derive_seed;
+pub use encoder::{
+    ConvEncoder,
+    EncoderKind,
+};
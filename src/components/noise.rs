@@ -1,10 +1,216 @@
-use candle_core::{
-    DType,
-    Device,
-    Result,
-    Tensor,
+//! Exploration noise for continuous control.
+//!
+//! All noise processes implement the [`ExplorationNoise`] trait. Their strength
+//! is scaled by a [`NoiseSchedule`], which decays the noise linearly or
+//! exponentially over steps or episodes. The agent resets the noise at every
+//! episode boundary, which e.g. restarts the [`OuNoise`] process and resamples
+//! the [`ParameterNoise`] perturbation.
+use {
+    candle_core::{
+        DType,
+        Device,
+        Result,
+        Shape,
+        Tensor,
+    },
+    rand::{
+        rngs::StdRng,
+        Rng,
+        SeedableRng,
+    },
+    rand_distr::StandardNormal,
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    strum::EnumIter,
+    std::{
+        fmt::Display,
+        ops::RangeInclusive,
+    },
 };
 
+
+/// Sample a [`Tensor`] of standard normal values from a seeded RNG.
+fn randn(
+    rng: &mut StdRng,
+    shape: &Shape,
+    device: &Device,
+) -> Result<Tensor> {
+    let values: Vec<f64> = rng
+        .sample_iter(StandardNormal)
+        .take(shape.elem_count())
+        .collect();
+    Tensor::from_vec(values, shape, device)
+}
+
+/// A source of exploration noise for the actions of an agent.
+///
+/// The noise is applied to the actions of the actor in [`ExplorationNoise::apply`],
+/// or, for noise in parameter space, to the parameters of the actor in
+/// [`ExplorationNoise::perturb_parameters`]. Both take the current `scale` of
+/// the [`NoiseSchedule`].
+pub trait ExplorationNoise: Send {
+    /// Add noise to the actions of the actor, either a single action or a
+    /// batch of shape `[N, ...]` where every row is perturbed independently.
+    fn apply(
+        &mut self,
+        actions: &Tensor,
+        scale: f64,
+    ) -> Result<Tensor>;
+
+    /// Perturb the parameters of the actor, which are then used for the rest
+    /// of the episode.
+    ///
+    /// Returns `None` if the noise does not act in parameter space.
+    fn perturb_parameters(
+        &mut self,
+        _parameters: &[Tensor],
+        _scale: f64,
+    ) -> Result<Option<Vec<Tensor>>> {
+        Ok(None)
+    }
+
+    /// Reset the noise at the end of an episode.
+    fn reset(&mut self) {}
+
+    /// Seed the random number generator of the noise.
+    fn seed(
+        &mut self,
+        seed: u64,
+    );
+
+    fn clone_box(&self) -> Box<dyn ExplorationNoise>;
+}
+
+impl Clone for Box<dyn ExplorationNoise> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum NoiseKind {
+    OrnsteinUhlenbeck,
+    Gaussian,
+    EpsilonRandom,
+    ParameterSpace,
+}
+
+impl Display for NoiseKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoiseKind::OrnsteinUhlenbeck => write!(f, "OrnsteinUhlenbeck"),
+            NoiseKind::Gaussian => write!(f, "Gaussian"),
+            NoiseKind::EpsilonRandom => write!(f, "EpsilonRandom"),
+            NoiseKind::ParameterSpace => write!(f, "ParameterSpace"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum NoiseDecay {
+    Constant,
+    Linear,
+    Exponential,
+}
+
+impl Display for NoiseDecay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoiseDecay::Constant => write!(f, "Constant"),
+            NoiseDecay::Linear => write!(f, "Linear"),
+            NoiseDecay::Exponential => write!(f, "Exponential"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum ScheduleUnit {
+    Steps,
+    Episodes,
+}
+
+impl Display for ScheduleUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleUnit::Steps => write!(f, "Steps"),
+            ScheduleUnit::Episodes => write!(f, "Episodes"),
+        }
+    }
+}
+
+/// Scales the noise down over the course of training.
+///
+/// The scale starts at 1.0 and decays towards `final_scale`:
+///
+/// - [`NoiseDecay::Constant`] keeps the scale at 1.0.
+/// - [`NoiseDecay::Linear`] reaches `final_scale` after `length` units and then stays there.
+/// - [`NoiseDecay::Exponential`] halves the distance to `final_scale` every `length` units.
+///
+/// # Fields
+///
+/// * `decay` - The shape of the decay.
+/// * `unit` - Whether the schedule advances per step or per episode.
+/// * `length` - The length of the decay in units (0 disables the decay).
+/// * `final_scale` - The scale the noise decays towards.
+/// * `steps` - The number of (training) steps taken so far.
+/// * `episodes` - The number of episodes finished so far.
+#[derive(Debug, Clone, Copy)]
+pub struct NoiseSchedule {
+    decay: NoiseDecay,
+    unit: ScheduleUnit,
+    length: usize,
+    final_scale: f64,
+    steps: usize,
+    episodes: usize,
+}
+impl NoiseSchedule {
+    pub fn new(
+        decay: NoiseDecay,
+        unit: ScheduleUnit,
+        length: usize,
+        final_scale: f64,
+    ) -> Self {
+        Self {
+            decay,
+            unit,
+            length,
+            final_scale,
+            steps: 0,
+            episodes: 0,
+        }
+    }
+
+    /// Advance the schedule by one step.
+    pub fn step(&mut self) {
+        self.steps += 1;
+    }
+
+    /// Advance the schedule by one episode.
+    pub fn end_episode(&mut self) {
+        self.episodes += 1;
+    }
+
+    /// Get the current scale of the noise.
+    pub fn scale(&self) -> f64 {
+        if self.length == 0 {
+            return 1.0
+        }
+        let t = match self.unit {
+            ScheduleUnit::Steps => self.steps,
+            ScheduleUnit::Episodes => self.episodes,
+        } as f64 / self.length as f64;
+
+        let progress = match self.decay {
+            NoiseDecay::Constant => 0.0,
+            NoiseDecay::Linear => t.min(1.0),
+            NoiseDecay::Exponential => 1.0 - 0.5_f64.powf(t),
+        };
+        1.0 + (self.final_scale - 1.0) * progress
+    }
+}
+
 /// The Ornstein-Uhlenbeck process.
 ///
 /// This process generates a noise that is correlated with the previous noise,
@@ -35,12 +241,18 @@ use candle_core::{
 /// `$\theta$`, with speed, `$\kappa$`, and volatility, `$\sigma$`.
 ///
 /// ---
+///
+/// The Wiener process is sampled from its own [`StdRng`], so the noise is
+/// reproducible after calling [`ExplorationNoise::seed`]. The process restarts
+/// from its initial state at every episode boundary.
 #[derive(Clone)]
 pub struct OuNoise {
     theta: f64,
     kappa: f64,
     sigma: f64,
+    initial_state: Tensor,
     state: Tensor,
+    rng: StdRng,
 }
 impl OuNoise {
     pub fn new(
@@ -50,16 +262,19 @@ impl OuNoise {
         size_action: usize,
         device: &Device,
     ) -> Result<Self> {
+        let initial_state = Tensor::ones(size_action, DType::F64, device)?;
         Ok(Self {
             theta,
             kappa,
             sigma,
-            state: Tensor::ones(size_action, DType::F64, device)?,
+            state: initial_state.clone(),
+            initial_state,
+            rng: StdRng::from_entropy(),
         })
     }
 
     pub fn sample(&mut self) -> Result<Tensor> {
-        let rand = Tensor::randn_like(&self.state, 0.0, 1.0)?;
+        let rand = randn(&mut self.rng, self.state.shape(), self.state.device())?;
         let dx = ((self.kappa * (self.theta - &self.state)?)? + (self.sigma * rand)?)?;
         self.state = (&self.state + dx)?;
         Ok(self.state.clone())
@@ -77,3 +292,199 @@ impl OuNoise {
         self.sigma
     }
 }
+
+impl ExplorationNoise for OuNoise {
+    fn apply(
+        &mut self,
+        actions: &Tensor,
+        scale: f64,
+    ) -> Result<Tensor> {
+        // a batch of actions runs one process per row, which all restart at
+        // the next reset
+        if self.state.shape() != actions.shape() {
+            self.state = self.initial_state.broadcast_as(actions.shape())?.contiguous()?;
+        }
+        actions + (self.sample()? * scale)?
+    }
+
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+    }
+
+    fn seed(
+        &mut self,
+        seed: u64,
+    ) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn clone_box(&self) -> Box<dyn ExplorationNoise> {
+        Box::new(self.clone())
+    }
+}
+
+/// Uncorrelated Gaussian noise with standard deviation `sigma` on the actions.
+#[derive(Clone)]
+pub struct GaussianNoise {
+    sigma: f64,
+    rng: StdRng,
+}
+impl GaussianNoise {
+    pub fn new(sigma: f64) -> Self {
+        Self {
+            sigma,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn sigma(&self) -> f64 {
+        self.sigma
+    }
+}
+
+impl ExplorationNoise for GaussianNoise {
+    fn apply(
+        &mut self,
+        actions: &Tensor,
+        scale: f64,
+    ) -> Result<Tensor> {
+        let rand = randn(&mut self.rng, actions.shape(), actions.device())?;
+        actions + (rand * (self.sigma * scale))?
+    }
+
+    fn seed(
+        &mut self,
+        seed: u64,
+    ) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn clone_box(&self) -> Box<dyn ExplorationNoise> {
+        Box::new(self.clone())
+    }
+}
+
+/// Replaces the actions by uniformly random actions with probability
+/// `epsilon`.
+///
+/// The random actions are sampled from `domain`, with one range per component
+/// of an action. A single range is used for all components.
+#[derive(Clone)]
+pub struct EpsilonRandomNoise {
+    epsilon: f64,
+    domain: Vec<RangeInclusive<f64>>,
+    rng: StdRng,
+}
+impl EpsilonRandomNoise {
+    pub fn new(
+        epsilon: f64,
+        domain: Vec<RangeInclusive<f64>>,
+    ) -> Self {
+        assert!(!domain.is_empty(), "The domain of the random actions is empty");
+        Self {
+            epsilon,
+            domain,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    pub fn domain(&self) -> &[RangeInclusive<f64>] {
+        &self.domain
+    }
+}
+
+impl ExplorationNoise for EpsilonRandomNoise {
+    fn apply(
+        &mut self,
+        actions: &Tensor,
+        scale: f64,
+    ) -> Result<Tensor> {
+        // every action of a batch is replaced independently
+        let epsilon = (self.epsilon * scale).clamp(0.0, 1.0);
+        let size_action = actions.dims().last().copied().unwrap_or(1).max(1);
+        let mut values = actions.to_dtype(DType::F64)?.flatten_all()?.to_vec1::<f64>()?;
+        for action in values.chunks_mut(size_action) {
+            if self.rng.gen::<f64>() < epsilon {
+                for (i, value) in action.iter_mut().enumerate() {
+                    let range = &self.domain[i.min(self.domain.len() - 1)];
+                    *value = self.rng.gen_range(range.clone());
+                }
+            }
+        }
+        Tensor::from_vec(values, actions.shape(), actions.device())?.to_dtype(actions.dtype())
+    }
+
+    fn seed(
+        &mut self,
+        seed: u64,
+    ) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn clone_box(&self) -> Box<dyn ExplorationNoise> {
+        Box::new(self.clone())
+    }
+}
+
+/// Gaussian noise with standard deviation `sigma` on the parameters of the
+/// actor, as in [Parameter Space Noise for Exploration](https://arxiv.org/abs/1706.01905).
+///
+/// The perturbation is sampled once per episode, which gives temporally
+/// consistent exploration. The actions themselves are left untouched.
+#[derive(Clone)]
+pub struct ParameterNoise {
+    sigma: f64,
+    rng: StdRng,
+}
+impl ParameterNoise {
+    pub fn new(sigma: f64) -> Self {
+        Self {
+            sigma,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn sigma(&self) -> f64 {
+        self.sigma
+    }
+}
+
+impl ExplorationNoise for ParameterNoise {
+    fn apply(
+        &mut self,
+        actions: &Tensor,
+        _scale: f64,
+    ) -> Result<Tensor> {
+        Ok(actions.clone())
+    }
+
+    fn perturb_parameters(
+        &mut self,
+        parameters: &[Tensor],
+        scale: f64,
+    ) -> Result<Option<Vec<Tensor>>> {
+        parameters
+            .iter()
+            .map(|p| {
+                let rand = randn(&mut self.rng, p.shape(), p.device())?.to_dtype(p.dtype())?;
+                p + (rand * (self.sigma * scale))?
+            })
+            .collect::<Result<Vec<Tensor>>>()
+            .map(Some)
+    }
+
+    fn seed(
+        &mut self,
+        seed: u64,
+    ) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn clone_box(&self) -> Box<dyn ExplorationNoise> {
+        Box::new(self.clone())
+    }
+}
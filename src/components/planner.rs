@@ -0,0 +1,405 @@
+//! Graph planners for the Sparse Graphical Memory
+//!
+//! This module contains the [`Planner`] which finds a path between two nodes
+//! of a [`StableGraph`]. The search strategy is chosen via [`PlannerMode`]:
+//!
+//! - [`PlannerMode::AStar`] uses a distance function between node weights as
+//!   the heuristic, which is admissible as long as it never overestimates the
+//!   edge weights along a path.
+//! - [`PlannerMode::Dijkstra`] is A* with a zero heuristic.
+//! - [`PlannerMode::FloydWarshall`] precomputes all shortest paths at once and
+//!   caches them, so that replanning is a simple lookup. Added nodes and added
+//!   or cheaper edges are folded into the cache in `O(n²)`, see
+//!   [`Planner::node_added`] and [`Planner::edge_added`]. Only removed nodes
+//!   and edges or more expensive edges [invalidate](Planner::invalidate) it.
+use {
+    ordered_float::OrderedFloat,
+    petgraph::{
+        algo::astar,
+        stable_graph::{
+            NodeIndex,
+            StableGraph,
+        },
+        visit::{
+            EdgeFiltered,
+            EdgeRef,
+            IntoEdgeReferences,
+        },
+        Directed,
+        Direction::{
+            Incoming,
+            Outgoing,
+        },
+    },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    strum::EnumIter,
+    std::{
+        collections::HashMap,
+        fmt::Display,
+    },
+};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum PlannerMode {
+    AStar,
+    Dijkstra,
+    FloydWarshall,
+}
+
+impl Display for PlannerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlannerMode::AStar => write!(f, "AStar"),
+            PlannerMode::Dijkstra => write!(f, "Dijkstra"),
+            PlannerMode::FloydWarshall => write!(f, "FloydWarshall"),
+        }
+    }
+}
+
+/// All-pairs shortest paths as computed by the Floyd-Warshall algorithm.
+///
+/// # Fields
+///
+/// * `positions` - Maps a [`NodeIndex`] to its row / column in the matrices.
+/// * `nodes` - Maps a row / column back to its [`NodeIndex`].
+/// * `dist` - The `n x n` matrix of shortest path costs.
+/// * `next` - The `n x n` matrix of the next hop on each shortest path.
+#[derive(Clone)]
+struct AllPairs {
+    positions: HashMap<NodeIndex, usize>,
+    nodes: Vec<NodeIndex>,
+    dist: Vec<f64>,
+    next: Vec<Option<usize>>,
+}
+impl AllPairs {
+    fn new<S>(
+        graph: &StableGraph<S, OrderedFloat<f64>, Directed>,
+        max_edge_cost: f64,
+    ) -> Self {
+        let nodes: Vec<NodeIndex> = graph.node_indices().collect();
+        let positions: HashMap<NodeIndex, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (*n, i))
+            .collect();
+
+        let n = nodes.len();
+        let mut dist = vec![f64::INFINITY; n * n];
+        let mut next = vec![None; n * n];
+
+        for i in 0..n {
+            dist[i * n + i] = 0.0;
+            next[i * n + i] = Some(i);
+        }
+
+        for edge in graph.edge_references() {
+            let weight = **edge.weight();
+            if max_edge_cost > 0.0 && weight > max_edge_cost {
+                continue;
+            }
+            let (i, j) = (positions[&edge.source()], positions[&edge.target()]);
+            if weight < dist[i * n + j] {
+                dist[i * n + j] = weight;
+                next[i * n + j] = Some(j);
+            }
+        }
+
+        for k in 0..n {
+            for i in 0..n {
+                if dist[i * n + k].is_infinite() {
+                    continue;
+                }
+                for j in 0..n {
+                    let via_k = dist[i * n + k] + dist[k * n + j];
+                    if via_k < dist[i * n + j] {
+                        dist[i * n + j] = via_k;
+                        next[i * n + j] = next[i * n + k];
+                    }
+                }
+            }
+        }
+
+        Self {
+            positions,
+            nodes,
+            dist,
+            next,
+        }
+    }
+
+    /// Add a node and its edges to the shortest paths.
+    ///
+    /// The matrices grow by one row and column, the paths from and to the
+    /// new node are derived from its edges, and every other path is improved
+    /// by going through the new node where that is cheaper.
+    fn add_node<S>(
+        &mut self,
+        graph: &StableGraph<S, OrderedFloat<f64>, Directed>,
+        node: NodeIndex,
+        max_edge_cost: f64,
+    ) {
+        let n = self.nodes.len();
+        let m = n + 1;
+        let v = n;
+
+        let mut dist = vec![f64::INFINITY; m * m];
+        let mut next = vec![None; m * m];
+        for i in 0..n {
+            dist[i * m..i * m + n].copy_from_slice(&self.dist[i * n..i * n + n]);
+            next[i * m..i * m + n].copy_from_slice(&self.next[i * n..i * n + n]);
+        }
+        dist[v * m + v] = 0.0;
+        next[v * m + v] = Some(v);
+
+        let usable = |weight: f64| max_edge_cost <= 0.0 || weight <= max_edge_cost;
+        for edge in graph.edges_directed(node, Incoming) {
+            let weight = **edge.weight();
+            let Some(&u) = self.positions.get(&edge.source()) else { continue };
+            if !usable(weight) {
+                continue;
+            }
+            for i in 0..n {
+                let via_u = dist[i * m + u] + weight;
+                if via_u < dist[i * m + v] {
+                    dist[i * m + v] = via_u;
+                    next[i * m + v] = if i == u { Some(v) } else { next[i * m + u] };
+                }
+            }
+        }
+        for edge in graph.edges_directed(node, Outgoing) {
+            let weight = **edge.weight();
+            let Some(&w) = self.positions.get(&edge.target()) else { continue };
+            if !usable(weight) {
+                continue;
+            }
+            for j in 0..n {
+                let via_w = weight + dist[w * m + j];
+                if via_w < dist[v * m + j] {
+                    dist[v * m + j] = via_w;
+                    next[v * m + j] = Some(w);
+                }
+            }
+        }
+
+        for i in 0..n {
+            if dist[i * m + v].is_infinite() {
+                continue;
+            }
+            for j in 0..n {
+                let via_v = dist[i * m + v] + dist[v * m + j];
+                if via_v < dist[i * m + j] {
+                    dist[i * m + j] = via_v;
+                    next[i * m + j] = next[i * m + v];
+                }
+            }
+        }
+
+        self.positions.insert(node, v);
+        self.nodes.push(node);
+        self.dist = dist;
+        self.next = next;
+    }
+
+    /// Improve the shortest paths with a new edge, or an edge whose cost was
+    /// lowered.
+    ///
+    /// Returns `false` if an endpoint is unknown, in which case the cache must
+    /// be rebuilt.
+    fn add_edge(
+        &mut self,
+        from: NodeIndex,
+        to: NodeIndex,
+        weight: f64,
+    ) -> bool {
+        let n = self.nodes.len();
+        let (Some(&u), Some(&v)) = (self.positions.get(&from), self.positions.get(&to)) else {
+            return false
+        };
+        for i in 0..n {
+            if self.dist[i * n + u].is_infinite() {
+                continue;
+            }
+            for j in 0..n {
+                let via_edge = self.dist[i * n + u] + weight + self.dist[v * n + j];
+                if via_edge < self.dist[i * n + j] {
+                    self.dist[i * n + j] = via_edge;
+                    self.next[i * n + j] = if i == u { Some(v) } else { self.next[i * n + u] };
+                }
+            }
+        }
+        true
+    }
+
+    fn path(
+        &self,
+        start: NodeIndex,
+        goal: NodeIndex,
+    ) -> Option<(f64, Vec<NodeIndex>)> {
+        let n = self.nodes.len();
+        let i = *self.positions.get(&start)?;
+        let j = *self.positions.get(&goal)?;
+
+        if self.dist[i * n + j].is_infinite() {
+            return None;
+        }
+
+        let mut path = vec![self.nodes[i]];
+        let mut curr = i;
+        while curr != j {
+            curr = self.next[curr * n + j]?;
+            path.push(self.nodes[curr]);
+        }
+        Some((self.dist[i * n + j], path))
+    }
+}
+
+/// A configurable path planner on top of the SGM graph.
+///
+/// # Fields
+///
+/// * `mode` - The search strategy, see [`PlannerMode`].
+/// * `max_plan_length` - Plans with more nodes than this are rejected (0 disables the cap).
+/// * `max_edge_cost` - Edges that cost more than this are ignored (0.0 disables the cap).
+/// * `all_pairs` - The cached all-pairs shortest paths for [`PlannerMode::FloydWarshall`].
+#[derive(Clone)]
+pub struct Planner {
+    mode: PlannerMode,
+    max_plan_length: usize,
+    max_edge_cost: f64,
+    all_pairs: Option<AllPairs>,
+}
+impl Planner {
+    pub fn new(
+        mode: PlannerMode,
+        max_plan_length: usize,
+        max_edge_cost: f64,
+    ) -> Self {
+        Self {
+            mode,
+            max_plan_length,
+            max_edge_cost,
+            all_pairs: None,
+        }
+    }
+
+    pub fn mode(&self) -> PlannerMode {
+        self.mode
+    }
+
+    pub fn max_plan_length(&self) -> usize {
+        self.max_plan_length
+    }
+
+    pub fn max_edge_cost(&self) -> f64 {
+        self.max_edge_cost
+    }
+
+    /// Forget the cached all-pairs shortest paths.
+    ///
+    /// This must be called whenever nodes or edges are removed from the graph
+    /// or an edge becomes more expensive, otherwise the
+    /// [`PlannerMode::FloydWarshall`] planner returns stale paths.
+    pub fn invalidate(&mut self) {
+        self.all_pairs = None;
+    }
+
+    /// Fold a node that was added to the graph, together with its edges, into
+    /// the cached all-pairs shortest paths.
+    pub fn node_added<S>(
+        &mut self,
+        graph: &StableGraph<S, OrderedFloat<f64>, Directed>,
+        node: NodeIndex,
+    ) {
+        let max_edge_cost = self.max_edge_cost;
+        if let Some(all_pairs) = self.all_pairs.as_mut() {
+            if all_pairs.positions.contains_key(&node) {
+                // the index was reused, so the cache is stale anyway
+                self.all_pairs = None;
+            } else {
+                all_pairs.add_node(graph, node, max_edge_cost);
+            }
+        }
+    }
+
+    /// Fold an edge that was added to the graph, or whose cost was lowered,
+    /// into the cached all-pairs shortest paths.
+    pub fn edge_added(
+        &mut self,
+        from: NodeIndex,
+        to: NodeIndex,
+        weight: f64,
+    ) {
+        if self.max_edge_cost > 0.0 && weight > self.max_edge_cost {
+            return;
+        }
+        if let Some(all_pairs) = self.all_pairs.as_mut() {
+            if !all_pairs.add_edge(from, to, weight) {
+                self.all_pairs = None;
+            }
+        }
+    }
+
+    /// Find the cheapest path from `start` to `goal`, including both.
+    ///
+    /// The heuristic `h` is only used in [`PlannerMode::AStar`] and is given
+    /// the weight of a node and the weight of the goal node. The path is only
+    /// guaranteed to be the cheapest if `h` never overestimates the cost to
+    /// the goal, so pass a zero heuristic when the edge costs are not on the
+    /// same scale as `h`.
+    ///
+    /// Returns `None` if there is no path, or if the path is longer than
+    /// `max_plan_length`.
+    pub fn plan<S, H>(
+        &mut self,
+        graph: &StableGraph<S, OrderedFloat<f64>, Directed>,
+        start: NodeIndex,
+        goal: NodeIndex,
+        h: H,
+    ) -> Option<Vec<NodeIndex>>
+    where
+        H: Fn(&S, &S) -> f64,
+    {
+        let max_edge_cost = self.max_edge_cost;
+        let filtered = EdgeFiltered::from_fn(graph, |e| {
+            max_edge_cost <= 0.0 || **e.weight() <= max_edge_cost
+        });
+
+        let path = match self.mode {
+            PlannerMode::AStar => {
+                let goal_weight = graph.node_weight(goal)?;
+                astar(
+                    &filtered,
+                    start,
+                    |n| n == goal,
+                    |e| *e.weight(),
+                    |n| OrderedFloat(h(&graph[n], goal_weight)),
+                ).map(|(_, path)| path)
+            },
+            PlannerMode::Dijkstra => {
+                astar(
+                    &filtered,
+                    start,
+                    |n| n == goal,
+                    |e| *e.weight(),
+                    |_| OrderedFloat(0.0),
+                ).map(|(_, path)| path)
+            },
+            PlannerMode::FloydWarshall => {
+                self.all_pairs
+                    .get_or_insert_with(|| AllPairs::new(graph, max_edge_cost))
+                    .path(start, goal)
+                    .map(|(_, path)| path)
+            },
+        }?;
+
+        if self.max_plan_length > 0 && path.len() > self.max_plan_length {
+            None
+        } else {
+            Some(path)
+        }
+    }
+}
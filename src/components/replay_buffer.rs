@@ -1,95 +1,301 @@
 use {
     crate::envs::TensorConvertible,
+    anyhow::{
+        anyhow,
+        bail,
+        ensure,
+    },
     candle_core::{
+        safetensors,
+        DType,
+        Device,
         Result,
         Tensor,
     },
+    polars::prelude::{
+        DataFrame,
+        DataType,
+        NamedFrom,
+        ParquetReader,
+        ParquetWriter,
+        SerReader,
+        Series,
+    },
+    ::safetensors::{
+        tensor::serialize_to_file,
+        SafeTensors,
+    },
     rand::{
         distributions::Uniform,
-        thread_rng,
+        rngs::StdRng,
         Rng,
+        SeedableRng,
+    },
+    std::{
+        collections::HashMap,
+        fs::File,
+        ops::Range,
+        path::Path,
     },
-    std::collections::VecDeque,
-    unzip_n::unzip_n,
 };
 
-unzip_n!(6);
+/// The names of the columns of a transition, as used in saved replay buffers.
+const COLUMN_NAMES: [&str; 6] = [
+    "state",
+    "action",
+    "reward",
+    "next_state",
+    "terminated",
+    "truncated",
+];
 
-/// A transition in the replay buffer.
+/// A single column of the replay buffer, e.g. all the states.
+///
+/// The values of each entry are flattened and stored contiguously on the host
+/// as `f64`, so that pushing is a simple copy into a preallocated slot and a
+/// batch is gathered into a single [`Tensor`] in one go.
 ///
 /// # Fields
 ///
-/// * `state` - The state tensor.
-/// * `action` - The action tensor.
-/// * `reward` - The reward tensor.
-/// * `next_state` - The next state tensor.
-/// * `terminated` - The terminated tensor.
-/// * `truncated` - The truncated tensor.
+/// * `data` - The preallocated values of `capacity` entries.
+/// * `shape` - The shape of a single entry.
+/// * `numel` - The number of values in a single entry.
+/// * `dtype` - The [`DType`] of the pushed tensors, which is restored on sampling.
 #[derive(Clone)]
-pub struct Transition {
-    state: Tensor,
-    action: Tensor,
-    reward: Tensor,
-    next_state: Tensor,
-    terminated: Tensor,
-    truncated: Tensor,
+struct Column {
+    data: Vec<f64>,
+    shape: Vec<usize>,
+    numel: usize,
+    dtype: DType,
 }
-impl Transition {
+impl Column {
     fn new(
-        state: &Tensor,
-        action: &Tensor,
-        reward: &Tensor,
-        next_state: &Tensor,
-        terminated: &Tensor,
-        truncated: &Tensor,
+        template: &Tensor,
+        capacity: usize,
+    ) -> Self {
+        let numel = template.elem_count();
+        Self {
+            data: vec![0.0; capacity * numel],
+            shape: template.dims().to_vec(),
+            numel,
+            dtype: template.dtype(),
+        }
+    }
+
+    /// Create a column from a batch of entries, with room for `capacity` entries.
+    fn from_batch(
+        batch: &Tensor,
+        capacity: usize,
+    ) -> Result<Self> {
+        let shape = batch.dims()[1..].to_vec();
+        let numel = shape.iter().product();
+        let mut data = batch.flatten_all()?.to_dtype(DType::F64)?.to_vec1::<f64>()?;
+        data.resize(capacity * numel, 0.0);
+        Ok(Self {
+            data,
+            shape,
+            numel,
+            dtype: batch.dtype(),
+        })
+    }
+
+    /// Check that a tensor has the shape of the entries of this column.
+    fn check(
+        &self,
+        value: &Tensor,
+    ) -> Result<()> {
+        if value.dims() != self.shape.as_slice() {
+            bail!(
+                "Expected a transition entry of shape {:?} but got {:?}",
+                self.shape,
+                value.dims(),
+            );
+        }
+        Ok(())
+    }
+
+    fn set(
+        &mut self,
+        slot: usize,
+        value: &Tensor,
+    ) -> Result<()> {
+        self.check(value)?;
+        let values = value.flatten_all()?.to_dtype(DType::F64)?.to_vec1::<f64>()?;
+        self.data[slot * self.numel..(slot + 1) * self.numel].copy_from_slice(&values);
+        Ok(())
+    }
+
+    fn get(
+        &self,
+        slot: usize,
+    ) -> &[f64] {
+        &self.data[slot * self.numel..(slot + 1) * self.numel]
+    }
+
+    fn entry(
+        &self,
+        slot: usize,
+        device: &Device,
+    ) -> Result<Tensor> {
+        Tensor::from_slice(self.get(slot), self.shape.as_slice(), device)?.to_dtype(self.dtype)
+    }
+
+    fn gather(
+        &self,
+        slots: &[usize],
+        device: &Device,
+    ) -> Result<Tensor> {
+        let mut values = Vec::with_capacity(slots.len() * self.numel);
+        for slot in slots {
+            values.extend_from_slice(self.get(*slot));
+        }
+        let mut shape = vec![slots.len()];
+        shape.extend_from_slice(&self.shape);
+        Tensor::from_vec(values, shape, device)?.to_dtype(self.dtype)
+    }
+
+    fn resized(
+        &self,
+        slots: &[usize],
+        capacity: usize,
     ) -> Self {
+        let mut data = Vec::with_capacity(capacity * self.numel);
+        for slot in slots {
+            data.extend_from_slice(self.get(*slot));
+        }
+        data.resize(capacity * self.numel, 0.0);
         Self {
-            state: state.clone(),
-            action: action.clone(),
-            reward: reward.clone(),
-            next_state: next_state.clone(),
-            terminated: terminated.clone(),
-            truncated: truncated.clone(),
+            data,
+            shape: self.shape.clone(),
+            numel: self.numel,
+            dtype: self.dtype,
         }
     }
 }
 
+/// The six columns of the replay buffer, in the order of a transition.
+#[derive(Clone)]
+struct Columns {
+    state: Column,
+    action: Column,
+    reward: Column,
+    next_state: Column,
+    terminated: Column,
+    truncated: Column,
+}
+impl Columns {
+    fn as_array(&self) -> [&Column; 6] {
+        [
+            &self.state,
+            &self.action,
+            &self.reward,
+            &self.next_state,
+            &self.terminated,
+            &self.truncated,
+        ]
+    }
+
+    fn as_array_mut(&mut self) -> [&mut Column; 6] {
+        [
+            &mut self.state,
+            &mut self.action,
+            &mut self.reward,
+            &mut self.next_state,
+            &mut self.terminated,
+            &mut self.truncated,
+        ]
+    }
+}
+
 /// A replay buffer for off-policy algorithms.
 ///
-/// The replay buffer is implemented as a simple ring buffer / VecDeque.
+/// The replay buffer is implemented as a ring buffer over preallocated,
+/// contiguous column storage. The columns are allocated on the first push, when
+/// the shapes of the transitions are known, and a random batch is gathered
+/// directly into one [`Tensor`] per column.
+///
+/// The buffer also keeps track of the episode boundaries: every transition is
+/// tagged with the episode it belongs to and its step index within that
+/// episode, and an episode ends with a terminated or truncated transition.
+/// Transitions are addressed by their index from the oldest (`0`) to the newest
+/// (`size - 1`) transition, so that whole (sub-)trajectories are simply ranges
+/// of indices, see [`ReplayBuffer::episodes`].
 ///
 /// # Fields
 ///
-/// * `buffer` - The buffer of transitions.
+/// * `columns` - The column storage, allocated on the first push.
+/// * `device` - The [`Device`] of the pushed tensors, used for the sampled batches.
 /// * `capacity` - The capacity of the buffer.
 /// * `size` - The current size of the buffer.
+/// * `head` - The slot the next transition is written to.
+/// * `episode` - The episode of the transition in each slot.
+/// * `step` - The step index within its episode of the transition in each slot.
+/// * `next_episode` - The episode of the next pushed transition.
+/// * `next_step` - The step index of the next pushed transition.
+/// * `rng` - The random number generator used for sampling, see [`ReplayBuffer::seed`].
 #[derive(Clone)]
 pub struct ReplayBuffer {
-    buffer: VecDeque<Transition>,
+    columns: Option<Columns>,
+    device: Device,
     capacity: usize,
     size: usize,
+    head: usize,
+    episode: Vec<usize>,
+    step: Vec<usize>,
+    next_episode: usize,
+    next_step: usize,
+    rng: StdRng,
 }
 impl ReplayBuffer {
     /// Create a new replay buffer with the given capacity.
     pub fn new(capacity: usize) -> Self {
         Self {
-            buffer: VecDeque::with_capacity(capacity),
+            columns: None,
+            device: Device::Cpu,
             capacity,
             size: 0,
+            head: 0,
+            episode: Vec::new(),
+            step: Vec::new(),
+            next_episode: 0,
+            next_step: 0,
+            rng: StdRng::from_entropy(),
         }
     }
 
+    /// Seed the random number generator used for sampling batches.
+    pub fn seed(
+        &mut self,
+        seed: u64,
+    ) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     /// Check if the buffer is full.
     pub fn is_full(&self) -> bool {
         self.size == self.capacity
     }
 
     /// Set capacity
+    ///
+    /// The newest transitions are kept if the buffer shrinks below its size.
     pub fn set_capacity(
         &mut self,
         capacity: usize,
     ) {
+        let slots: Vec<usize> = self.slots().skip(self.size.saturating_sub(capacity)).collect();
+        self.columns = self.columns.as_ref().map(|columns| Columns {
+            state: columns.state.resized(&slots, capacity),
+            action: columns.action.resized(&slots, capacity),
+            reward: columns.reward.resized(&slots, capacity),
+            next_state: columns.next_state.resized(&slots, capacity),
+            terminated: columns.terminated.resized(&slots, capacity),
+            truncated: columns.truncated.resized(&slots, capacity),
+        });
         self.capacity = capacity;
+        self.size = slots.len();
+        self.head = if capacity == 0 { 0 } else { self.size % capacity };
+        self.rebuild_episodes();
     }
 
     /// Get the size of the buffer.
@@ -97,10 +303,68 @@ impl ReplayBuffer {
         self.size
     }
 
+    /// The slot of the transition at `index`, counting from the oldest transition.
+    fn slot(
+        &self,
+        index: usize,
+    ) -> usize {
+        let oldest = if self.is_full() { self.head } else { 0 };
+        (oldest + index) % self.capacity
+    }
+
+    /// The occupied slots, from the oldest to the newest transition.
+    fn slots(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.size).map(|i| self.slot(i))
+    }
+
+    /// Whether the transition in `slot` ends its episode.
+    fn is_done(
+        &self,
+        slot: usize,
+    ) -> bool {
+        self.columns.as_ref().is_some_and(|columns| {
+            columns.terminated.get(slot).iter().any(|v| *v != 0.0)
+                || columns.truncated.get(slot).iter().any(|v| *v != 0.0)
+        })
+    }
+
+    /// Tag the transition in `slot` with the current episode and step index.
+    fn record_step(
+        &mut self,
+        slot: usize,
+    ) {
+        self.episode[slot] = self.next_episode;
+        self.step[slot] = self.next_step;
+
+        if self.is_done(slot) {
+            self.next_episode += 1;
+            self.next_step = 0;
+        } else {
+            self.next_step += 1;
+        }
+    }
+
+    /// Recompute the episode boundaries from the termination flags.
+    ///
+    /// The step indices of the oldest episode start at 0 again, even if its
+    /// first steps were already evicted.
+    fn rebuild_episodes(&mut self) {
+        let slots: Vec<usize> = self.slots().collect();
+        self.episode = vec![0; self.capacity];
+        self.step = vec![0; self.capacity];
+        self.next_episode = 0;
+        self.next_step = 0;
+
+        for slot in slots {
+            self.record_step(slot);
+        }
+    }
+
     /// Push a transition into the buffer.
     ///
-    /// If the buffer is full, the oldest transition is removed to make room for
-    /// the new transition.
+    /// If the buffer is full, the oldest transition is overwritten to make room
+    /// for the new transition. Fails if the shapes of the transition differ
+    /// from the ones pushed before, without changing the buffer.
     pub fn push(
         &mut self,
         state: &Tensor,
@@ -109,82 +373,366 @@ impl ReplayBuffer {
         next_state: &Tensor,
         terminated: &Tensor,
         truncated: &Tensor,
-    ) {
-        if self.size == self.capacity {
-            self.buffer.pop_front();
-        } else {
+    ) -> Result<()> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+
+        let capacity = self.capacity;
+        if self.columns.is_none() {
+            self.device = state.device().clone();
+            self.episode = vec![0; capacity];
+            self.step = vec![0; capacity];
+        }
+        let columns = self.columns.get_or_insert_with(|| Columns {
+            state: Column::new(state, capacity),
+            action: Column::new(action, capacity),
+            reward: Column::new(reward, capacity),
+            next_state: Column::new(next_state, capacity),
+            terminated: Column::new(terminated, capacity),
+            truncated: Column::new(truncated, capacity),
+        });
+
+        // check all entries first, so a failed push leaves the slot untouched
+        let values = [state, action, reward, next_state, terminated, truncated];
+        for (column, value) in columns.as_array_mut().into_iter().zip(values) {
+            column.check(value)?;
+        }
+        for (column, value) in columns.as_array_mut().into_iter().zip(values) {
+            column.set(self.head, value)?;
+        }
+        self.record_step(self.head);
+
+        self.head = (self.head + 1) % self.capacity;
+        if self.size < self.capacity {
             self.size += 1;
         }
-        self.buffer.push_back(Transition::new(
-            state, action, reward, next_state, terminated, truncated,
-        ));
+        Ok(())
     }
 
     /// Sample a random batch of transitions from the buffer.
     ///
-    /// When the size of the buffer is less than the batch size, `None` is returned.
+    /// When the size of the buffer is less than the batch size, or the buffer
+    /// is empty, `None` is returned.
     #[allow(clippy::type_complexity)]
     pub fn random_batch(
-        &self,
+        &mut self,
         batch_size: usize,
     ) -> Result<Option<(Tensor, Tensor, Tensor, Tensor, Tensor, Tensor)>> {
-        if self.size < batch_size {
-            Ok(None)
-        } else {
-            let transition_to_tuple =
-                |t: &Transition| -> Result<(Tensor, Tensor, Tensor, Tensor, Tensor, Tensor)> {
-                    Ok((
-                        t.state.unsqueeze(0)?,
-                        t.action.unsqueeze(0)?,
-                        t.reward.unsqueeze(0)?,
-                        t.next_state.unsqueeze(0)?,
-                        t.terminated.unsqueeze(0)?,
-                        t.truncated.unsqueeze(0)?,
-                    ))
-                };
+        if self.size == 0 || self.size < batch_size {
+            return Ok(None)
+        }
+
+        // Until the buffer is full, the occupied slots are exactly 0..size
+        let slots: Vec<usize> = (&mut self.rng)
+            .sample_iter(Uniform::from(0..self.size))
+            .take(batch_size)
+            .collect();
+
+        self.gather(&slots)
+    }
+
+    /// Gather the transitions in `slots` into one batched [`Tensor`] per column.
+    #[allow(clippy::type_complexity)]
+    fn gather(
+        &self,
+        slots: &[usize],
+    ) -> Result<Option<(Tensor, Tensor, Tensor, Tensor, Tensor, Tensor)>> {
+        let Some(columns) = &self.columns else {
+            return Ok(None)
+        };
 
-            let transitions: Vec<&Transition> = thread_rng()
-                .sample_iter(Uniform::from(0..self.size))
-                .take(batch_size)
-                .map(|i| self.buffer.get(i).unwrap())
-                .collect();
+        Ok(Some((
+            columns.state.gather(slots, &self.device)?,
+            columns.action.gather(slots, &self.device)?,
+            columns.reward.gather(slots, &self.device)?,
+            columns.next_state.gather(slots, &self.device)?,
+            columns.terminated.gather(slots, &self.device)?,
+            columns.truncated.gather(slots, &self.device)?,
+        )))
+    }
 
-            let (states, actions, rewards, next_states, terminateds, truncateds) =
-                transitions
-                .into_iter()
-                .map(transition_to_tuple)
-                .collect::<Result<Vec<(Tensor, Tensor, Tensor, Tensor, Tensor, Tensor)>>>()?
-                .into_iter()
-                .unzip_n_vec();
+    /// Get the (possibly partial) episodes in the buffer as ranges of indices,
+    /// from the oldest to the newest episode.
+    ///
+    /// The oldest episode may have lost its first steps to eviction, and the
+    /// newest episode may still be running.
+    pub fn episodes(&self) -> Vec<Range<usize>> {
+        let mut episodes = Vec::new();
+        let mut start = 0;
 
-            Ok(Some((
-                Tensor::cat(&states, 0)?,
-                Tensor::cat(&actions, 0)?,
-                Tensor::cat(&rewards, 0)?,
-                Tensor::cat(&next_states, 0)?,
-                Tensor::cat(&terminateds, 0)?,
-                Tensor::cat(&truncateds, 0)?,
-            )))
+        for index in 1..=self.size {
+            if index == self.size || self.episode[self.slot(index)] != self.episode[self.slot(index - 1)] {
+                episodes.push(start..index);
+                start = index;
+            }
         }
+        episodes
+    }
+
+    /// Get the step index within its episode of the transition at `index`.
+    pub fn step_index(
+        &self,
+        index: usize,
+    ) -> Option<usize> {
+        (index < self.size).then(|| self.step[self.slot(index)])
+    }
+
+    /// Get the transitions in `range` as a batch, in order.
+    ///
+    /// When the range is empty or out of bounds, `None` is returned.
+    #[allow(clippy::type_complexity)]
+    pub fn trajectory(
+        &self,
+        range: Range<usize>,
+    ) -> Result<Option<(Tensor, Tensor, Tensor, Tensor, Tensor, Tensor)>> {
+        if range.is_empty() || range.end > self.size {
+            return Ok(None)
+        }
+        let slots: Vec<usize> = range.map(|i| self.slot(i)).collect();
+        self.gather(&slots)
+    }
+
+    /// Sample a random sub-trajectory of at most `max_length` transitions
+    /// (0 means the whole episode) from a uniformly sampled episode.
+    ///
+    /// When the buffer is empty, `None` is returned.
+    #[allow(clippy::type_complexity)]
+    pub fn random_trajectory(
+        &mut self,
+        max_length: usize,
+    ) -> Result<Option<(Tensor, Tensor, Tensor, Tensor, Tensor, Tensor)>> {
+        let episodes = self.episodes();
+        if episodes.is_empty() {
+            return Ok(None)
+        }
+
+        let episode = episodes[self.rng.gen_range(0..episodes.len())].clone();
+        let length = if max_length == 0 { episode.len() } else { max_length.min(episode.len()) };
+        let start = self.rng.gen_range(episode.start..=episode.end - length);
+
+        self.trajectory(start..start + length)
+    }
+
+    /// Get the states visited in `range` as `Observation`s, including the
+    /// next state of the last transition.
+    pub fn trajectory_states<S: TensorConvertible>(
+        &self,
+        range: Range<usize>,
+    ) -> Vec<S> {
+        let Some(columns) = &self.columns else {
+            return Vec::new()
+        };
+        if range.is_empty() || range.end > self.size {
+            return Vec::new()
+        }
+        let to_state = |column: &Column, slot: usize| {
+            <S>::from_tensor(column.entry(slot, &self.device).unwrap())
+        };
+
+        let mut states: Vec<S> = range
+            .clone()
+            .map(|i| to_state(&columns.state, self.slot(i)))
+            .collect();
+        states.push(to_state(&columns.next_state, self.slot(range.end - 1)));
+
+        states
     }
 
     /// Get all states in the buffer as `Observation`s.
     ///
-    /// This collects all the [`Tensor`] states in the buffer and returns them
-    /// as `Observation`s.
+    /// This collects all the [`Tensor`] states in the buffer, from the oldest
+    /// to the newest, and returns them as `Observation`s.
     pub fn all_states<S: TensorConvertible>(&self) -> Vec<S> {
-        let mut states: Vec<S> = self
-            .buffer
-            .iter()
-            .map(|t| <S>::from_tensor(t.state.clone()))
-            .collect();
+        self.trajectory_states(0..self.size)
+    }
 
-        states.extend(
-            self.buffer
-                .back()
-                .map(|t| <S>::from_tensor(t.next_state.clone())),
-        );
+    /// Create a replay buffer from the batched columns of a transition, from
+    /// the oldest to the newest transition.
+    ///
+    /// The capacity is increased to the number of transitions if necessary,
+    /// and batches are sampled with a random number generator seeded by `seed`.
+    fn from_batches(
+        capacity: usize,
+        batches: [Tensor; 6],
+        seed: u64,
+    ) -> Result<Self> {
+        let size = batches[0].dim(0)?;
+        let capacity = capacity.max(size);
+        let [state, action, reward, next_state, terminated, truncated] = batches;
 
-        states
+        let mut buffer = Self {
+            device: state.device().clone(),
+            columns: Some(Columns {
+                state: Column::from_batch(&state, capacity)?,
+                action: Column::from_batch(&action, capacity)?,
+                reward: Column::from_batch(&reward, capacity)?,
+                next_state: Column::from_batch(&next_state, capacity)?,
+                terminated: Column::from_batch(&terminated, capacity)?,
+                truncated: Column::from_batch(&truncated, capacity)?,
+            }),
+            capacity,
+            size,
+            head: if capacity == 0 { 0 } else { size % capacity },
+            episode: Vec::new(),
+            step: Vec::new(),
+            next_episode: 0,
+            next_step: 0,
+            rng: StdRng::seed_from_u64(seed),
+        };
+        buffer.rebuild_episodes();
+        Ok(buffer)
+    }
+
+    /// Save the transitions in the buffer, from the oldest to the newest.
+    ///
+    /// The format is chosen by the file extension:
+    ///
+    /// - `.parquet` writes one column per dimension, e.g. `state_0`, `state_1`,
+    ///   ..., `terminated_0`, so the dataset is easy to inspect in a notebook.
+    ///   The termination flags are stored as booleans and `f32` columns keep
+    ///   their type. Entries are flattened, so only one dimensional entries
+    ///   come back with their shape.
+    /// - `.safetensors` writes one tensor per column of the transition, with
+    ///   the dtype and shape of the pushed tensors, and the `observation_size`
+    ///   and `action_size` in the `__metadata__` of the file.
+    pub fn save<P: AsRef<Path> + ?Sized>(
+        &self,
+        path: &P,
+    ) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let Some(columns) = &self.columns else {
+            bail!("Cannot save an empty replay buffer to {path:?}")
+        };
+        let slots: Vec<usize> = self.slots().collect();
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("parquet") => {
+                let mut series = Vec::new();
+                for (name, column) in COLUMN_NAMES.iter().zip(columns.as_array()) {
+                    let rows = column
+                        .gather(&slots, &Device::Cpu)?
+                        .to_dtype(DType::F64)?
+                        .reshape((slots.len(), column.numel))?
+                        .to_vec2::<f64>()?;
+                    let flag = matches!(*name, "terminated" | "truncated");
+
+                    for i in 0..column.numel {
+                        let name = format!("{name}_{i}");
+                        let values = rows.iter().map(|row| row[i]);
+                        series.push(if flag {
+                            Series::new(&name, values.map(|v| v != 0.0).collect::<Vec<bool>>())
+                        } else if column.dtype == DType::F32 {
+                            Series::new(&name, values.map(|v| v as f32).collect::<Vec<f32>>())
+                        } else {
+                            Series::new(&name, values.collect::<Vec<f64>>())
+                        });
+                    }
+                }
+                let mut df = DataFrame::new(series)?;
+                ParquetWriter::new(File::create(path)?).finish(&mut df)?;
+            },
+            Some("safetensors") => {
+                let mut tensors = Vec::new();
+                for (name, column) in COLUMN_NAMES.iter().zip(columns.as_array()) {
+                    tensors.push((name.to_string(), column.gather(&slots, &Device::Cpu)?));
+                }
+                let metadata = HashMap::from([
+                    ("observation_size".to_string(), columns.state.numel.to_string()),
+                    ("action_size".to_string(), columns.action.numel.to_string()),
+                ]);
+                serialize_to_file(tensors, &Some(metadata), path)?;
+            },
+            _ => bail!("Unknown replay buffer format: {path:?}"),
+        }
+        Ok(())
+    }
+
+    /// Load a replay buffer that was written by [`ReplayBuffer::save`].
+    ///
+    /// The capacity is increased to the number of saved transitions if
+    /// necessary, the sampled batches are placed on `device` and batches are
+    /// sampled with a random number generator seeded by `seed`.
+    pub fn load<P: AsRef<Path> + ?Sized>(
+        path: &P,
+        capacity: usize,
+        device: &Device,
+        seed: u64,
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+
+        let batches: Vec<Tensor> = match path.extension().and_then(|e| e.to_str()) {
+            Some("parquet") => {
+                let df = ParquetReader::new(File::open(path)?).finish()?;
+                let size = df.height();
+
+                COLUMN_NAMES
+                    .iter()
+                    .map(|name| {
+                        let prefix = format!("{name}_");
+                        let numel = df
+                            .get_column_names()
+                            .iter()
+                            .filter(|c| c.strip_prefix(&prefix).is_some_and(|i| i.parse::<usize>().is_ok()))
+                            .count();
+                        ensure!(numel > 0, "Missing column {name} in {path:?}");
+
+                        let mut data = vec![0.0; size * numel];
+                        for i in 0..numel {
+                            let values = df.column(&format!("{prefix}{i}"))?.cast(&DataType::Float64)?;
+                            for (row, value) in values.f64()?.into_no_null_iter().enumerate() {
+                                data[row * numel + i] = value;
+                            }
+                        }
+                        let dtype = match df.column(&format!("{prefix}0"))?.dtype() {
+                            DataType::Boolean => DType::U8,
+                            DataType::Float32 => DType::F32,
+                            _ => DType::F64,
+                        };
+                        Ok(Tensor::from_vec(data, (size, numel), device)?.to_dtype(dtype)?)
+                    })
+                    .collect::<anyhow::Result<_>>()?
+            },
+            Some("safetensors") => {
+                let data = std::fs::read(path)?;
+                let (_, header) = SafeTensors::read_metadata(&data)?;
+                let metadata = header.metadata().clone().unwrap_or_default();
+                let size_of = |key: &str| {
+                    metadata
+                        .get(key)
+                        .ok_or_else(|| anyhow!("Missing {key} in the metadata of {path:?}"))?
+                        .parse::<usize>()
+                        .map_err(|e| anyhow!("Invalid {key} in the metadata of {path:?}: {e}"))
+                };
+                let observation_size = size_of("observation_size")?;
+                let action_size = size_of("action_size")?;
+
+                let mut tensors = safetensors::load(path, device)?;
+                let mut take = |name: &str| {
+                    tensors.remove(name).ok_or_else(|| anyhow!("Missing tensor {name} in {path:?}"))
+                };
+
+                let batches = COLUMN_NAMES
+                    .iter()
+                    .map(|name| take(name))
+                    .collect::<anyhow::Result<Vec<Tensor>>>()?;
+
+                ensure!(
+                    batches[0].elem_count() == batches[0].dim(0)? * observation_size,
+                    "States in {path:?} do not match the observation size {observation_size}",
+                );
+                ensure!(
+                    batches[1].elem_count() == batches[1].dim(0)? * action_size,
+                    "Actions in {path:?} do not match the action size {action_size}",
+                );
+                batches
+            },
+            _ => bail!("Unknown replay buffer format: {path:?}"),
+        };
+
+        let batches: [Tensor; 6] = batches
+            .try_into()
+            .map_err(|_| anyhow!("Expected {} columns in {path:?}", COLUMN_NAMES.len()))?;
+        Ok(Self::from_batches(capacity, batches, seed)?)
     }
 }
@@ -0,0 +1,167 @@
+use {
+    super::ReplayBuffer,
+    candle_core::{
+        Result,
+        Tensor,
+    },
+};
+
+
+/// A named replay buffer that is sampled with a fixed ratio.
+///
+/// # Fields
+///
+/// * `name` - The name of the source, e.g. `"pretraining"` or `"demonstrations"`.
+/// * `buffer` - The transitions of the source.
+/// * `ratio` - The relative weight of the source in a training batch.
+#[derive(Clone)]
+pub struct ReplaySource {
+    pub name: String,
+    pub buffer: ReplayBuffer,
+    pub ratio: f64,
+}
+
+/// Split `batch_size` over sources with the given `ratios`.
+///
+/// The counts are proportional to the ratios and always sum to `batch_size`
+/// (unless all ratios are zero), the remainder is given to the sources with
+/// the largest fractional parts.
+fn split_batch(
+    batch_size: usize,
+    ratios: &[f64],
+) -> Vec<usize> {
+    let total: f64 = ratios.iter().sum();
+    if total <= 0.0 {
+        return vec![0; ratios.len()]
+    }
+
+    let exact: Vec<f64> = ratios.iter().map(|r| r / total * batch_size as f64).collect();
+    let mut counts: Vec<usize> = exact.iter().map(|e| e.floor() as usize).collect();
+
+    let mut by_remainder: Vec<usize> = (0..ratios.len()).collect();
+    by_remainder.sort_by(|&a, &b| (exact[b] - exact[b].floor()).total_cmp(&(exact[a] - exact[a].floor())));
+    let missing = batch_size - counts.iter().sum::<usize>();
+    for i in by_remainder.into_iter().take(missing) {
+        counts[i] += 1;
+    }
+    counts
+}
+
+/// A set of additional replay sources that are mixed into the training batches
+/// of an off-policy agent, next to its own (online) replay buffer.
+///
+/// This allows keeping e.g. pretraining data or demonstrations around, and
+/// controlling exactly how much of each training batch comes from them.
+#[derive(Clone, Default)]
+pub struct ReplayMixture {
+    sources: Vec<ReplaySource>,
+}
+impl ReplayMixture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sources(&self) -> &[ReplaySource] {
+        &self.sources
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Get the buffer of the source with the given name.
+    pub fn get(
+        &self,
+        name: &str,
+    ) -> Option<&ReplayBuffer> {
+        self.sources.iter().find(|s| s.name == name).map(|s| &s.buffer)
+    }
+
+    /// Add a source, replacing any existing source with the same name.
+    pub fn add(
+        &mut self,
+        name: &str,
+        buffer: ReplayBuffer,
+        ratio: f64,
+    ) {
+        self.remove(name);
+        self.sources.push(ReplaySource {
+            name: name.to_string(),
+            buffer,
+            ratio,
+        });
+    }
+
+    /// Remove a source and return its buffer.
+    pub fn remove(
+        &mut self,
+        name: &str,
+    ) -> Option<ReplayBuffer> {
+        let position = self.sources.iter().position(|s| s.name == name)?;
+        Some(self.sources.remove(position).buffer)
+    }
+
+    /// Set the ratio of a source, returns whether the source exists.
+    pub fn set_ratio(
+        &mut self,
+        name: &str,
+        ratio: f64,
+    ) -> bool {
+        self.sources
+            .iter_mut()
+            .find(|s| s.name == name)
+            .map(|s| s.ratio = ratio)
+            .is_some()
+    }
+
+    pub fn sources_mut(&mut self) -> impl Iterator<Item = &mut ReplaySource> {
+        self.sources.iter_mut()
+    }
+
+    /// Sample a random batch of transitions from the `online` buffer and all
+    /// sources, split according to their ratios.
+    ///
+    /// Empty buffers and buffers with a ratio of zero are left out and their
+    /// share is distributed over the others. When a buffer holds fewer
+    /// transitions than its share of the batch, `None` is returned.
+    #[allow(clippy::type_complexity)]
+    pub fn random_batch(
+        &mut self,
+        online: &mut ReplayBuffer,
+        online_ratio: f64,
+        batch_size: usize,
+    ) -> Result<Option<(Tensor, Tensor, Tensor, Tensor, Tensor, Tensor)>> {
+        let mut buffers: Vec<(&mut ReplayBuffer, f64)> = vec![(online, online_ratio)];
+        buffers.extend(self.sources.iter_mut().map(|s| (&mut s.buffer, s.ratio)));
+        buffers.retain(|(buffer, ratio)| buffer.size() > 0 && *ratio > 0.0);
+
+        let ratios: Vec<f64> = buffers.iter().map(|(_, ratio)| *ratio).collect();
+        let counts = split_batch(batch_size, &ratios);
+
+        let mut batches = Vec::new();
+        for ((buffer, _), count) in buffers.into_iter().zip(counts) {
+            if count == 0 {
+                continue;
+            }
+            match buffer.random_batch(count)? {
+                Some(batch) => batches.push(batch),
+                None => return Ok(None),
+            }
+        }
+        if batches.is_empty() {
+            return Ok(None)
+        }
+
+        let column = |f: fn(&(Tensor, Tensor, Tensor, Tensor, Tensor, Tensor)) -> &Tensor| {
+            Tensor::cat(&batches.iter().map(f).collect::<Vec<_>>(), 0)
+        };
+        Ok(Some((
+            column(|b| &b.0)?,
+            column(|b| &b.1)?,
+            column(|b| &b.2)?,
+            column(|b| &b.3)?,
+            column(|b| &b.4)?,
+            column(|b| &b.5)?,
+        )))
+    }
+}
@@ -0,0 +1,24 @@
+/// Derive the seed of the random number stream `stream` from a master seed.
+///
+/// Every source of randomness (the replay buffer, the exploration noise, the
+/// network initialization, ...) gets its own named stream, so that the streams
+/// are independent of each other and adding a new one does not change the
+/// existing ones. The stream name is hashed with FNV-1a and mixed into the
+/// master seed with the SplitMix64 finalizer, which is stable across platforms
+/// and compiler versions (unlike [`std::hash::DefaultHasher`]).
+pub fn derive_seed(
+    seed: u64,
+    stream: &str,
+) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in stream.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    let mut z = seed ^ hash;
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
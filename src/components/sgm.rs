@@ -15,11 +15,20 @@ use {
     },
     ordered_float::OrderedFloat,
     petgraph::{
+        algo::{
+            dijkstra,
+            tarjan_scc,
+        },
         dot::Dot,
         stable_graph::{
             NodeIndex,
             StableGraph,
         },
+        unionfind::UnionFind,
+        visit::{
+            EdgeRef,
+            IntoEdgeReferences,
+        },
         Directed,
     },
     serde::{
@@ -27,7 +36,10 @@ use {
         Deserialize,
     },
     std::{
-        collections::HashMap,
+        collections::{
+            HashMap,
+            HashSet,
+        },
         fmt::{
             Debug,
             Display,
@@ -52,6 +64,133 @@ impl Display for DistanceMode {
     }
 }
 
+/// Attempt and success counts for traversing a single edge of the graph.
+///
+/// The `distance` is the original edge weight, which is scaled by the
+/// reliability of the edge to get the expected cost of traversing it.
+#[derive(Clone, Copy, Debug)]
+pub struct EdgeStats {
+    pub distance: f64,
+    pub attempts: usize,
+    pub successes: usize,
+}
+impl EdgeStats {
+    pub fn new(distance: f64) -> Self {
+        Self {
+            distance,
+            attempts: 0,
+            successes: 0,
+        }
+    }
+
+    /// Record the outcome of an attempt to traverse the edge.
+    pub fn record(
+        &mut self,
+        success: bool,
+    ) {
+        self.attempts += 1;
+        if success {
+            self.successes += 1;
+        }
+    }
+
+    /// The estimated probability of successfully traversing the edge.
+    ///
+    /// This uses an optimistic prior of a single success, so that untried
+    /// edges have a reliability of 1.0 and keep their original cost.
+    pub fn reliability(&self) -> f64 {
+        (self.successes + 1) as f64 / (self.attempts + 1) as f64
+    }
+
+    /// The expected cost of traversing the edge, when failed attempts have to
+    /// be repeated until one succeeds.
+    pub fn expected_cost(&self) -> f64 {
+        self.distance / self.reliability()
+    }
+
+    /// Whether we are confident enough that the edge cannot be traversed.
+    pub fn is_unreliable(
+        &self,
+        min_attempts: usize,
+        min_reliability: f64,
+    ) -> bool {
+        self.attempts >= min_attempts && self.reliability() < min_reliability
+    }
+}
+
+/// Health metrics of a graph.
+///
+/// # Fields
+///
+/// * `nodes` - The number of nodes.
+/// * `edges` - The number of (directed) edges.
+/// * `weak_components` - The number of weakly connected components.
+/// * `strong_components` - The number of strongly connected components.
+/// * `average_degree` - The average out-degree of the nodes.
+/// * `diameter` - The largest weighted shortest path between any two connected nodes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GraphStats {
+    pub nodes: usize,
+    pub edges: usize,
+    pub weak_components: usize,
+    pub strong_components: usize,
+    pub average_degree: f64,
+    pub diameter: f64,
+}
+
+/// Per-episode statistics of the planning process of an agent.
+///
+/// # Fields
+///
+/// * `localized` - Whether start and goal were both localized in the graph.
+/// * `plan_length` - The number of waypoints in the first plan of the episode.
+/// * `edge_removals` - The number of edges that were pruned during the episode.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlanStats {
+    pub localized: bool,
+    pub plan_length: usize,
+    pub edge_removals: usize,
+}
+
+/// Compute the [`GraphStats`] of the given graph.
+pub fn graph_stats<S>(graph: &StableGraph<S, OrderedFloat<f64>, Directed>) -> GraphStats {
+    let nodes = graph.node_count();
+    let edges = graph.edge_count();
+
+    let mut components = UnionFind::new(graph.node_bound());
+    for edge in graph.edge_references() {
+        components.union(edge.source().index(), edge.target().index());
+    }
+    let weak_components = graph
+        .node_indices()
+        .map(|n| components.find(n.index()))
+        .collect::<HashSet<usize>>()
+        .len();
+
+    let strong_components = tarjan_scc(graph).len();
+
+    let average_degree = if nodes > 0 {
+        edges as f64 / nodes as f64
+    } else {
+        0.0
+    };
+
+    let diameter = graph
+        .node_indices()
+        .flat_map(|n| dijkstra(graph, n, None, |e| *e.weight()).into_values())
+        .max()
+        .map_or(0.0, |d| *d);
+
+    GraphStats {
+        nodes,
+        edges,
+        weak_components,
+        strong_components,
+        average_degree,
+        diameter,
+    }
+}
+
 /// Return a dotviz representation of the given graph.
 pub fn dot<S: Debug>(graph: &StableGraph<S, OrderedFloat<f64>, Directed>) -> String {
     format!("{:?}", Dot::new(graph)).to_string()
@@ -177,6 +316,41 @@ where
     edges_to_replenish
 }
 
+/// Construct a sparse graph from the given states.
+///
+/// The states are visited in order, and each state that is two-way consistent
+/// with the graph so far is added as a node. For more details, see
+/// [`ReplayBuffer::construct_sgm`].
+#[allow(clippy::type_complexity)]
+pub fn construct_sgm_from_states<S, D>(
+    states: &[S],
+    d: D,
+    maxdist: f64,
+    tau: f64,
+) -> (
+    StableGraph<S, OrderedFloat<f64>, Directed>,
+    HashMap<S, NodeIndex>,
+)
+where
+    S: Clone + Eq + Hash,
+    D: Fn(&S, &S) -> f64,
+{
+    // initialize the SGM data structures
+    let mut graph: StableGraph<S, OrderedFloat<f64>, Directed> = StableGraph::default();
+    let mut indices: HashMap<S, NodeIndex> = HashMap::new();
+
+    // iterate over the set of nodes
+    for s1 in states.iter() {
+
+        if is_two_consistent(&graph, s1, &d, tau) {
+            let (edges_from, edges_to) = get_edges(&graph, &indices, s1, &d, maxdist);
+            add_node_to_graph(&mut graph, &mut indices, s1, edges_from, edges_to);
+        }
+    }
+
+    (graph, indices)
+}
+
 impl ReplayBuffer {
     /// Construct a sparse graph from the replay buffer.
     ///
@@ -190,6 +364,7 @@ impl ReplayBuffer {
     /// * `d` - The distance function.
     /// * `maxdist` - The maximum distance between two nodes in the graph.
     /// * `tau` - The tau parameter to vary the graph sparsity.
+    #[allow(clippy::type_complexity)]
     pub fn construct_sgm<S, D>(
         &self,
         d: D,
@@ -203,19 +378,6 @@ impl ReplayBuffer {
         S: Clone + Eq + Hash + TensorConvertible,
         D: Fn(&S, &S) -> f64,
     {
-        // initialize the SGM data structures
-        let mut graph: StableGraph<S, OrderedFloat<f64>, Directed> = StableGraph::default();
-        let mut indices: HashMap<S, NodeIndex> = HashMap::new();
-
-        // iterate over the set of nodes in the buffer
-        for s1 in self.all_states::<S>().iter() {
-
-            if is_two_consistent(&graph, s1, &d, tau) {
-                let (edges_from, edges_to) = get_edges(&graph, &indices, s1, &d, maxdist);
-                add_node_to_graph(&mut graph, &mut indices, s1, edges_from, edges_to);
-            }
-        }
-
-        (graph, indices)
+        construct_sgm_from_states(&self.all_states::<S>(), d, maxdist, tau)
     }
 }
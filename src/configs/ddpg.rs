@@ -1,5 +1,9 @@
 use {
-    super::RenderableConfig,
+    super::{
+        RenderableConfig,
+        NoiseConfig,
+        EncoderConfig,
+    },
     serde::{
         Serialize,
         Deserialize,
@@ -29,10 +33,16 @@ pub struct DDPG_Config {
     pub replay_buffer_capacity: usize,
     // The training batch size for each training iteration.
     pub training_batch_size: usize,
-    // Ornstein-Uhlenbeck process parameters.
-    pub ou_theta: f64,
-    pub ou_kappa: f64,
-    pub ou_sigma: f64,
+    // The share of each training batch sampled from the online replay buffer,
+    // relative to the ratios of any additional replay sources.
+    pub replay_online_ratio: f64,
+    // The ratio with which the pretraining data is kept as a separate replay
+    // source after pretraining (0.0 keeps it in the online buffer instead).
+    pub pretrain_replay_ratio: f64,
+    // The exploration noise and its decay schedule.
+    pub noise: NoiseConfig,
+    // The encoder in front of the Actor and Critic networks.
+    pub encoder: EncoderConfig,
 }
 impl Default for DDPG_Config {
     fn default() -> Self {
@@ -45,9 +55,10 @@ impl Default for DDPG_Config {
             hidden_2_size: 256,
             replay_buffer_capacity: 1_000,
             training_batch_size: 64,
-            ou_theta: 0.0,
-            ou_kappa: 0.15,
-            ou_sigma: 0.2,
+            replay_online_ratio: 1.0,
+            pretrain_replay_ratio: 0.0,
+            noise: NoiseConfig::default(),
+            encoder: EncoderConfig::default(),
         }
     }
 }
@@ -62,9 +73,10 @@ impl DDPG_Config {
         hidden_2_size: usize,
         replay_buffer_capacity: usize,
         training_batch_size: usize,
-        ou_theta: f64,
-        ou_kappa: f64,
-        ou_sigma: f64,
+        replay_online_ratio: f64,
+        pretrain_replay_ratio: f64,
+        noise: NoiseConfig,
+        encoder: EncoderConfig,
     ) -> Self {
         Self {
             actor_learning_rate,
@@ -75,9 +87,10 @@ impl DDPG_Config {
             hidden_2_size,
             replay_buffer_capacity,
             training_batch_size,
-            ou_theta,
-            ou_kappa,
-            ou_sigma,
+            replay_online_ratio,
+            pretrain_replay_ratio,
+            noise,
+            encoder,
         }
     }
 }
@@ -95,8 +108,8 @@ impl RenderableConfig for DDPG_Config {
         let hidden_2_size = self.hidden_2_size;
         let buffer_size = self.replay_buffer_capacity;
         let batch_size = self.training_batch_size;
-        let ou_kappa = self.ou_kappa;
-        let ou_sigma = self.ou_sigma;
+        let online_ratio = self.replay_online_ratio;
+        let pretrain_ratio = self.pretrain_replay_ratio;
 
         ui.separator();
         ui.label("DDPG Options");
@@ -108,8 +121,11 @@ impl RenderableConfig for DDPG_Config {
         ui.add(Label::new(format!("Hidden 2 size: {hidden_2_size}")));
         ui.add(Label::new(format!("Buffer size: {buffer_size}")));
         ui.add(Label::new(format!("Batch size: {batch_size}")));
-        ui.add(Label::new(format!("OU Kappa (speed): {ou_kappa}")));
-        ui.add(Label::new(format!("OU Sigma (volatility): {ou_sigma}")));
+        ui.add(Label::new(format!("Online replay ratio: {online_ratio}")));
+        ui.add(Label::new(format!("Pretraining replay ratio: {pretrain_ratio}")));
+
+        self.noise.render_immutable(ui);
+        self.encoder.render_immutable(ui);
     }
 
     fn render_mutable(
@@ -155,14 +171,17 @@ impl RenderableConfig for DDPG_Config {
                 .text("Batch size"),
         );
         ui.add(
-            Slider::new(&mut self.ou_kappa, 0.0..=1.0)
-                .step_by(0.001)
-                .text("OU Kappa (speed)"),
+            Slider::new(&mut self.replay_online_ratio, 0.0..=1.0)
+                .step_by(0.01)
+                .text("Online replay ratio"),
         );
         ui.add(
-            Slider::new(&mut self.ou_sigma, 0.0..=1.0)
-                .step_by(0.001)
-                .text("OU Sigma (volatility)"),
+            Slider::new(&mut self.pretrain_replay_ratio, 0.0..=1.0)
+                .step_by(0.01)
+                .text("Pretraining replay ratio"),
         );
+
+        self.noise.render_mutable(ui);
+        self.encoder.render_mutable(ui);
     }
 }
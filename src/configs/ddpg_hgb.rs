@@ -3,6 +3,8 @@ use {
         RenderableConfig,
         DDPG_Config,
         DistanceMode,
+        EvictionPolicy,
+        PlannerMode,
     },
     serde::{
         Serialize,
@@ -13,7 +15,9 @@ use {
         Label,
         Slider,
         Button,
+        ComboBox,
     },
+    strum::IntoEnumIterator,
 };
 
 
@@ -32,6 +36,18 @@ pub struct DDPG_HGB_Config {
     pub sgm_waypoint_reward: f64,
     pub sgm_maxdist: f64,
     pub sgm_tau: f64,
+    // Planner parameters (a cap of 0 disables it)
+    pub sgm_planner: PlannerMode,
+    pub sgm_max_plan_length: usize,
+    pub sgm_max_edge_cost: f64,
+    // Edges are only pruned after enough attempts with a low success rate
+    pub sgm_prune_min_attempts: usize,
+    pub sgm_prune_reliability: f64,
+    // Whether to skip waypoints when the goal or a later waypoint is close
+    pub sgm_shortcut: bool,
+    // The number of candidate states kept in the graph memory (0 is unbounded)
+    pub sgm_memory_capacity: usize,
+    pub sgm_memory_eviction: EvictionPolicy,
 }
 impl Default for DDPG_HGB_Config {
     fn default() -> Self {
@@ -45,6 +61,14 @@ impl Default for DDPG_HGB_Config {
             sgm_waypoint_reward: 1.0,
             sgm_maxdist: 1.0,
             sgm_tau: 0.4,
+            sgm_planner: PlannerMode::AStar,
+            sgm_max_plan_length: 0,
+            sgm_max_edge_cost: 0.0,
+            sgm_prune_min_attempts: 3,
+            sgm_prune_reliability: 0.3,
+            sgm_shortcut: true,
+            sgm_memory_capacity: 10_000,
+            sgm_memory_eviction: EvictionPolicy::Fifo,
         }
     }
 }
@@ -60,6 +84,14 @@ impl DDPG_HGB_Config {
         sgm_waypoint_reward: f64,
         sgm_maxdist: f64,
         sgm_tau: f64,
+        sgm_planner: PlannerMode,
+        sgm_max_plan_length: usize,
+        sgm_max_edge_cost: f64,
+        sgm_prune_min_attempts: usize,
+        sgm_prune_reliability: f64,
+        sgm_shortcut: bool,
+        sgm_memory_capacity: usize,
+        sgm_memory_eviction: EvictionPolicy,
     ) -> Self {
         Self {
             ddpg,
@@ -71,6 +103,14 @@ impl DDPG_HGB_Config {
             sgm_waypoint_reward,
             sgm_maxdist,
             sgm_tau,
+            sgm_planner,
+            sgm_max_plan_length,
+            sgm_max_edge_cost,
+            sgm_prune_min_attempts,
+            sgm_prune_reliability,
+            sgm_shortcut,
+            sgm_memory_capacity,
+            sgm_memory_eviction,
         }
     }
 }
@@ -90,6 +130,14 @@ impl RenderableConfig for DDPG_HGB_Config {
         let waypoint_reward = self.sgm_waypoint_reward;
         let maxdist = self.sgm_maxdist;
         let tau = self.sgm_tau;
+        let planner = self.sgm_planner;
+        let max_plan_length = self.sgm_max_plan_length;
+        let max_edge_cost = self.sgm_max_edge_cost;
+        let prune_min_attempts = self.sgm_prune_min_attempts;
+        let prune_reliability = self.sgm_prune_reliability;
+        let shortcut = self.sgm_shortcut;
+        let memory_capacity = self.sgm_memory_capacity;
+        let memory_eviction = self.sgm_memory_eviction;
 
         ui.separator();
         ui.label("SGM Options");
@@ -101,6 +149,14 @@ impl RenderableConfig for DDPG_HGB_Config {
         ui.add(Label::new(format!("Waypoint reward: {waypoint_reward:#.2}")));
         ui.add(Label::new(format!("Max distance: {maxdist:#.2}")));
         ui.add(Label::new(format!("Tau: {tau:#.2}")));
+        ui.add(Label::new(format!("Planner: {planner}")));
+        ui.add(Label::new(format!("Max plan length: {max_plan_length}")));
+        ui.add(Label::new(format!("Max edge cost: {max_edge_cost:#.2}")));
+        ui.add(Label::new(format!("Prune min attempts: {prune_min_attempts}")));
+        ui.add(Label::new(format!("Prune reliability: {prune_reliability:#.2}")));
+        ui.add(Label::new(format!("Shortcut plan: {shortcut}")));
+        ui.add(Label::new(format!("Memory capacity: {memory_capacity}")));
+        ui.add(Label::new(format!("Memory eviction: {memory_eviction}")));
     }
 
     fn render_mutable(
@@ -153,5 +209,52 @@ impl RenderableConfig for DDPG_HGB_Config {
                 .step_by(0.01)
                 .text("Tau"),
         );
+        ComboBox::from_label("Planner")
+            .selected_text(format!("{}", self.sgm_planner))
+            .show_ui(ui, |ui| {
+                for planner in PlannerMode::iter() {
+                    ui.selectable_value(
+                        &mut self.sgm_planner,
+                        planner,
+                        format!("{}", planner),
+                    );
+                }
+            }
+        );
+        ui.add(
+            Slider::new(&mut self.sgm_max_plan_length, 0..=100)
+                .text("Max plan length"),
+        );
+        ui.add(
+            Slider::new(&mut self.sgm_max_edge_cost, 0.0..=10.0)
+                .step_by(0.01)
+                .text("Max edge cost"),
+        );
+        ui.add(
+            Slider::new(&mut self.sgm_prune_min_attempts, 1..=50)
+                .text("Prune min attempts"),
+        );
+        ui.add(
+            Slider::new(&mut self.sgm_prune_reliability, 0.0..=1.0)
+                .step_by(0.01)
+                .text("Prune reliability"),
+        );
+        ui.checkbox(&mut self.sgm_shortcut, "Shortcut plan");
+        ui.add(
+            Slider::new(&mut self.sgm_memory_capacity, 0..=1_000_000)
+                .text("Memory capacity"),
+        );
+        ComboBox::from_label("Memory eviction")
+            .selected_text(format!("{}", self.sgm_memory_eviction))
+            .show_ui(ui, |ui| {
+                for eviction in EvictionPolicy::iter() {
+                    ui.selectable_value(
+                        &mut self.sgm_memory_eviction,
+                        eviction,
+                        format!("{}", eviction),
+                    );
+                }
+            }
+        );
     }
 }
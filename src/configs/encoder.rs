@@ -0,0 +1,127 @@
+use {
+    super::{
+        RenderableConfig,
+        EncoderKind,
+    },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    egui::{
+        Ui,
+        Label,
+        Slider,
+        ComboBox,
+    },
+    strum::IntoEnumIterator,
+};
+
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EncoderConfig {
+    // The kind of encoder in front of the Actor and Critic networks.
+    pub kind: EncoderKind,
+    // The shape of the image at the end of the observation vector.
+    pub image_channels: usize,
+    pub image_size: usize,
+    // The number of channels of the convolutional layers.
+    pub conv_channels: usize,
+    // The number of features the image is encoded into.
+    pub feature_size: usize,
+    // Whether the values in front of the image are passed on next to the features.
+    pub include_state: bool,
+}
+impl Default for EncoderConfig {
+    fn default() -> Self {
+        Self {
+            kind: EncoderKind::None,
+            image_channels: 3,
+            image_size: 0,
+            conv_channels: 16,
+            feature_size: 64,
+            include_state: false,
+        }
+    }
+}
+impl EncoderConfig {
+    pub fn new(
+        kind: EncoderKind,
+        image_channels: usize,
+        image_size: usize,
+        conv_channels: usize,
+        feature_size: usize,
+        include_state: bool,
+    ) -> Self {
+        Self {
+            kind,
+            image_channels,
+            image_size,
+            conv_channels,
+            feature_size,
+            include_state,
+        }
+    }
+}
+
+impl RenderableConfig for EncoderConfig {
+    fn render_immutable(
+        &self,
+        ui: &mut Ui,
+    ) {
+        let kind = self.kind;
+        let image_channels = self.image_channels;
+        let image_size = self.image_size;
+        let conv_channels = self.conv_channels;
+        let feature_size = self.feature_size;
+        let include_state = self.include_state;
+
+        ui.separator();
+        ui.label("Encoder Options");
+        ui.add(Label::new(format!("Encoder: {kind}")));
+        if kind == EncoderKind::Conv {
+            ui.add(Label::new(format!("Image: {image_channels}x{image_size}x{image_size}")));
+            ui.add(Label::new(format!("Conv channels: {conv_channels}")));
+            ui.add(Label::new(format!("Feature size: {feature_size}")));
+            ui.add(Label::new(format!("Include state: {include_state}")));
+        }
+    }
+
+    fn render_mutable(
+        &mut self,
+        ui: &mut Ui,
+    ) {
+        ui.separator();
+        ui.label("Encoder Options");
+        ComboBox::from_label("Encoder")
+            .selected_text(format!("{}", self.kind))
+            .show_ui(ui, |ui| {
+                for kind in EncoderKind::iter() {
+                    ui.selectable_value(
+                        &mut self.kind,
+                        kind,
+                        format!("{}", kind),
+                    );
+                }
+            }
+        );
+        if self.kind == EncoderKind::Conv {
+            ui.add(
+                Slider::new(&mut self.image_channels, 1..=8)
+                    .text("Image channels"),
+            );
+            ui.add(
+                Slider::new(&mut self.image_size, 0..=64)
+                    .text("Image size"),
+            );
+            ui.add(
+                Slider::new(&mut self.conv_channels, 1..=64)
+                    .text("Conv channels"),
+            );
+            ui.add(
+                Slider::new(&mut self.feature_size, 1..=512)
+                    .text("Feature size"),
+            );
+            ui.checkbox(&mut self.include_state, "Include state");
+        }
+    }
+}
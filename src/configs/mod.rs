@@ -1,12 +1,24 @@
 mod train;
+mod noise;
 mod ddpg;
 mod ddpg_hgb;
+mod encoder;
 
 pub use train::TrainConfig;
+pub use noise::NoiseConfig;
+pub use encoder::EncoderConfig;
 pub use ddpg::DDPG_Config;
 pub use ddpg_hgb::DDPG_HGB_Config;
 
-use crate::components::sgm::DistanceMode;
+use crate::components::{
+    sgm::DistanceMode,
+    EvictionPolicy,
+    PlannerMode,
+    NoiseKind,
+    EncoderKind,
+    NoiseDecay,
+    ScheduleUnit,
+};
 use egui::Ui;
 
 
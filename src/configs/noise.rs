@@ -0,0 +1,216 @@
+use {
+    super::{
+        RenderableConfig,
+        NoiseKind,
+        NoiseDecay,
+        ScheduleUnit,
+    },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    egui::{
+        Ui,
+        Label,
+        Slider,
+        ComboBox,
+    },
+    strum::IntoEnumIterator,
+};
+
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NoiseConfig {
+    // The kind of exploration noise.
+    pub kind: NoiseKind,
+    // Ornstein-Uhlenbeck process parameters.
+    pub ou_theta: f64,
+    pub ou_kappa: f64,
+    pub ou_sigma: f64,
+    // The standard deviation of the Gaussian action noise.
+    pub gaussian_sigma: f64,
+    // The probability of taking a uniformly random action.
+    pub epsilon: f64,
+    // The standard deviation of the noise on the actor parameters.
+    pub parameter_sigma: f64,
+    // The decay schedule of the noise (a decay length of 0 disables it).
+    pub decay: NoiseDecay,
+    pub decay_unit: ScheduleUnit,
+    pub decay_length: usize,
+    pub final_scale: f64,
+}
+impl Default for NoiseConfig {
+    fn default() -> Self {
+        Self {
+            kind: NoiseKind::OrnsteinUhlenbeck,
+            ou_theta: 0.0,
+            ou_kappa: 0.15,
+            ou_sigma: 0.2,
+            gaussian_sigma: 0.1,
+            epsilon: 0.1,
+            parameter_sigma: 0.05,
+            decay: NoiseDecay::Constant,
+            decay_unit: ScheduleUnit::Episodes,
+            decay_length: 0,
+            final_scale: 0.1,
+        }
+    }
+}
+impl NoiseConfig {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        kind: NoiseKind,
+        ou_theta: f64,
+        ou_kappa: f64,
+        ou_sigma: f64,
+        gaussian_sigma: f64,
+        epsilon: f64,
+        parameter_sigma: f64,
+        decay: NoiseDecay,
+        decay_unit: ScheduleUnit,
+        decay_length: usize,
+        final_scale: f64,
+    ) -> Self {
+        Self {
+            kind,
+            ou_theta,
+            ou_kappa,
+            ou_sigma,
+            gaussian_sigma,
+            epsilon,
+            parameter_sigma,
+            decay,
+            decay_unit,
+            decay_length,
+            final_scale,
+        }
+    }
+}
+
+impl RenderableConfig for NoiseConfig {
+    fn render_immutable(
+        &self,
+        ui: &mut Ui,
+    ) {
+        let kind = self.kind;
+        let ou_kappa = self.ou_kappa;
+        let ou_sigma = self.ou_sigma;
+        let gaussian_sigma = self.gaussian_sigma;
+        let epsilon = self.epsilon;
+        let parameter_sigma = self.parameter_sigma;
+        let decay = self.decay;
+        let decay_unit = self.decay_unit;
+        let decay_length = self.decay_length;
+        let final_scale = self.final_scale;
+
+        ui.separator();
+        ui.label("Exploration Noise Options");
+        ui.add(Label::new(format!("Noise: {kind}")));
+        match kind {
+            NoiseKind::OrnsteinUhlenbeck => {
+                ui.add(Label::new(format!("OU Kappa (speed): {ou_kappa}")));
+                ui.add(Label::new(format!("OU Sigma (volatility): {ou_sigma}")));
+            },
+            NoiseKind::Gaussian => {
+                ui.add(Label::new(format!("Gaussian Sigma: {gaussian_sigma}")));
+            },
+            NoiseKind::EpsilonRandom => {
+                ui.add(Label::new(format!("Epsilon: {epsilon}")));
+            },
+            NoiseKind::ParameterSpace => {
+                ui.add(Label::new(format!("Parameter Sigma: {parameter_sigma}")));
+            },
+        }
+        ui.add(Label::new(format!("Decay: {decay}")));
+        ui.add(Label::new(format!("Decay length: {decay_length} {decay_unit}")));
+        ui.add(Label::new(format!("Final scale: {final_scale}")));
+    }
+
+    fn render_mutable(
+        &mut self,
+        ui: &mut Ui,
+    ) {
+        ui.separator();
+        ui.label("Exploration Noise Options");
+        ComboBox::from_label("Noise")
+            .selected_text(format!("{}", self.kind))
+            .show_ui(ui, |ui| {
+                for kind in NoiseKind::iter() {
+                    ui.selectable_value(
+                        &mut self.kind,
+                        kind,
+                        format!("{}", kind),
+                    );
+                }
+            }
+        );
+        match self.kind {
+            NoiseKind::OrnsteinUhlenbeck => {
+                ui.add(
+                    Slider::new(&mut self.ou_kappa, 0.0..=1.0)
+                        .step_by(0.001)
+                        .text("OU Kappa (speed)"),
+                );
+                ui.add(
+                    Slider::new(&mut self.ou_sigma, 0.0..=1.0)
+                        .step_by(0.001)
+                        .text("OU Sigma (volatility)"),
+                );
+            },
+            NoiseKind::Gaussian => {
+                ui.add(
+                    Slider::new(&mut self.gaussian_sigma, 0.0..=1.0)
+                        .step_by(0.001)
+                        .text("Gaussian Sigma"),
+                );
+            },
+            NoiseKind::EpsilonRandom => {
+                ui.add(
+                    Slider::new(&mut self.epsilon, 0.0..=1.0)
+                        .step_by(0.001)
+                        .text("Epsilon"),
+                );
+            },
+            NoiseKind::ParameterSpace => {
+                ui.add(
+                    Slider::new(&mut self.parameter_sigma, 0.0..=1.0)
+                        .step_by(0.001)
+                        .text("Parameter Sigma"),
+                );
+            },
+        }
+        ComboBox::from_label("Decay")
+            .selected_text(format!("{}", self.decay))
+            .show_ui(ui, |ui| {
+                for decay in NoiseDecay::iter() {
+                    ui.selectable_value(
+                        &mut self.decay,
+                        decay,
+                        format!("{}", decay),
+                    );
+                }
+            }
+        );
+        ComboBox::from_label("Decay unit")
+            .selected_text(format!("{}", self.decay_unit))
+            .show_ui(ui, |ui| {
+                for unit in ScheduleUnit::iter() {
+                    ui.selectable_value(
+                        &mut self.decay_unit,
+                        unit,
+                        format!("{}", unit),
+                    );
+                }
+            }
+        );
+        ui.add(
+            Slider::new(&mut self.decay_length, 0..=100_000)
+                .text("Decay length"),
+        );
+        ui.add(
+            Slider::new(&mut self.final_scale, 0.0..=1.0)
+                .step_by(0.01)
+                .text("Final scale"),
+        );
+    }
+}
@@ -24,6 +24,8 @@ pub struct TrainConfig {
     initial_random_actions: usize,
     // The RunMode
     run_mode: RunMode,
+    // The master seed that all random number streams are derived from.
+    seed: u64,
 }
 impl Default for TrainConfig {
     fn default() -> Self {
@@ -32,6 +34,7 @@ impl Default for TrainConfig {
             training_iterations: 30,
             initial_random_actions: 500,
             run_mode: RunMode::Train,
+            seed: 42,
         }
     }
 }
@@ -41,12 +44,14 @@ impl TrainConfig {
         training_iterations: usize,
         initial_random_actions: usize,
         run_mode: RunMode,
+        seed: u64,
     ) -> Self {
         Self {
             max_episodes,
             training_iterations,
             initial_random_actions,
             run_mode,
+            seed,
         }
     }
 }
@@ -64,6 +69,9 @@ impl TrainConfig {
     pub fn run_mode(&self) -> RunMode {
         self.run_mode.clone()
     }
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
     pub fn set_max_episodes(&mut self, max_episodes: usize) {
         self.max_episodes = max_episodes;
     }
@@ -76,6 +84,9 @@ impl TrainConfig {
     pub fn set_run_mode(&mut self, run_mode: RunMode) {
         self.run_mode = run_mode;
     }
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
 }
 
 impl RenderableConfig for TrainConfig {
@@ -97,6 +108,10 @@ impl RenderableConfig for TrainConfig {
             Slider::new(&mut self.initial_random_actions, 0..=1000)
                 .text("Initial Random Actions")
         );
+        ui.add(
+            Slider::new(&mut self.seed, 0..=1000)
+                .text("Seed")
+        );
         ui.horizontal(|ui| {
             ui.radio_value(&mut self.run_mode, RunMode::Train, "Train");
             ui.radio_value(&mut self.run_mode, RunMode::Test, "Test");
@@ -111,6 +126,7 @@ impl RenderableConfig for TrainConfig {
         let training_iterations = self.training_iterations;
         let initial_random_actions = self.initial_random_actions;
         let run_mode = self.run_mode.clone();
+        let seed = self.seed;
 
         ui.separator();
         ui.label("Run Options (Train)");
@@ -118,5 +134,6 @@ impl RenderableConfig for TrainConfig {
         ui.add(Label::new(format!("Training Iterations: {training_iterations}")));
         ui.add(Label::new(format!("Initial Random Actions: {initial_random_actions}")));
         ui.add(Label::new(format!("Run Mode: {run_mode:?}")));
+        ui.add(Label::new(format!("Seed: {seed}")));
     }
 }
\ No newline at end of file
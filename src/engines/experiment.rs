@@ -1,7 +1,10 @@
 use {
     super::{
         super::util::write_config,
-        run::loop_off_policy,
+        run::{
+            loop_off_policy,
+            loop_off_policy_with_hook,
+        },
         ParamAlg,
         ParamEnv,
     },
@@ -10,6 +13,15 @@ use {
             Algorithm,
             OffPolicyAlgorithm,
             SaveableAlgorithm,
+            HgbAlgorithm,
+        },
+        components::{
+            derive_seed,
+            sgm::{
+                graph_stats,
+                GraphStats,
+                PlanStats,
+            },
         },
         envs::{
             Environment,
@@ -37,6 +49,47 @@ use {
     tracing::warn,
 };
 
+/// The seeds of a single repetition of an experiment, all derived from the
+/// master seed in the [`TrainConfig`].
+///
+/// # Fields
+///
+/// * `run` - The index of the repetition.
+/// * `algorithm` - The seed passed to [`Algorithm::seed`].
+/// * `training` - The seed of the [`TrainConfig`] of the training loop.
+/// * `pretraining` - The seed of the [`TrainConfig`] of the pretraining loop.
+/// * `environment` - The seed of the environment configs, see [`Environment::seed_config`].
+#[derive(Debug, Clone, Copy, Serialize)]
+struct RunSeeds {
+    run: usize,
+    algorithm: u64,
+    training: u64,
+    pretraining: u64,
+    environment: u64,
+}
+impl RunSeeds {
+    fn new(
+        seed: u64,
+        run: usize,
+    ) -> Self {
+        let seed = derive_seed(seed, &format!("run_{run}"));
+        Self {
+            run,
+            algorithm: derive_seed(seed, "algorithm"),
+            training: derive_seed(seed, "training"),
+            pretraining: derive_seed(seed, "pretraining"),
+            environment: derive_seed(seed, "environment"),
+        }
+    }
+}
+
+/// All seeds of an experiment, as written to `seeds.ron`.
+#[derive(Serialize)]
+struct ExperimentSeeds {
+    seed: u64,
+    runs: Vec<RunSeeds>,
+}
+
 /// Run an experiment with an off-policy algorithm.
 ///
 /// # Arguments
@@ -65,6 +118,110 @@ where
     Alg::Config: Clone + Serialize,
     Obs: Clone + TensorConvertible,
     Act: Clone + TensorConvertible + Sampleable,
+{
+    run_experiment(
+        path,
+        n_repetitions,
+        init_env,
+        init_alg,
+        train_config,
+        load_model,
+        pretrain_train_config,
+        pretrain_env_config,
+        device,
+        |_| None,
+    )
+}
+
+/// Run an experiment with a graph-based off-policy algorithm.
+///
+/// This is the same as [`run_experiment_off_policy`], but additionally records
+/// the [`GraphStats`] and [`PlanStats`] of the agent after every episode next
+/// to the returns.
+///
+/// # Arguments
+///
+/// * `path` - The path to the directory where the collected data will be stored.
+/// * `n_repetitions` - The number of repeated, identical runs to perform.
+/// * `env_config` - The configuration for the environment.
+/// * `alg_config` - The configuration for the algorithm.
+/// * `device` - The device to run the experiment on.
+#[allow(clippy::too_many_arguments)]
+pub fn run_experiment_hgb<Alg, Env, Obs, Act>(
+    path: &dyn AsRef<Path>,
+    n_repetitions: usize,
+    init_env: ParamEnv<Env, Obs, Act>,
+    init_alg: ParamAlg<Alg>,
+    train_config: TrainConfig,
+    load_model: Option<(String, String)>,
+    pretrain_train_config: Option<TrainConfig>,
+    pretrain_env_config: Option<Env::Config>,
+    device: &Device,
+) -> Result<()>
+where
+    Env: Clone + Environment<Action = Act, Observation = Obs>,
+    Env::Config: Clone + Serialize,
+    Alg: Clone + Algorithm + OffPolicyAlgorithm + SaveableAlgorithm + HgbAlgorithm<Env>,
+    Alg::Config: Clone + Serialize,
+    Obs: Clone + TensorConvertible,
+    Act: Clone + TensorConvertible + Sampleable,
+{
+    run_experiment(
+        path,
+        n_repetitions,
+        init_env,
+        init_alg,
+        train_config,
+        load_model,
+        pretrain_train_config,
+        pretrain_env_config,
+        device,
+        |alg: &Alg| Some((graph_stats(alg.graph()), *alg.plan_stats())),
+    )
+}
+
+/// Turn the per-episode telemetry of run `n` into columns of a [`DataFrame`].
+fn telemetry_columns(
+    n: usize,
+    telemetry: &[(GraphStats, PlanStats)],
+) -> Vec<Series> {
+    let column = |name: &str| format!("run_{n}_{name}");
+    let (graphs, plans): (Vec<GraphStats>, Vec<PlanStats>) = telemetry.iter().copied().unzip();
+
+    vec![
+        Series::new(&column("graph_nodes"), graphs.iter().map(|g| g.nodes as u64).collect::<Vec<_>>()),
+        Series::new(&column("graph_edges"), graphs.iter().map(|g| g.edges as u64).collect::<Vec<_>>()),
+        Series::new(&column("graph_weak_components"), graphs.iter().map(|g| g.weak_components as u64).collect::<Vec<_>>()),
+        Series::new(&column("graph_strong_components"), graphs.iter().map(|g| g.strong_components as u64).collect::<Vec<_>>()),
+        Series::new(&column("graph_average_degree"), graphs.iter().map(|g| g.average_degree).collect::<Vec<_>>()),
+        Series::new(&column("graph_diameter"), graphs.iter().map(|g| g.diameter).collect::<Vec<_>>()),
+        Series::new(&column("localized"), plans.iter().map(|p| p.localized).collect::<Vec<_>>()),
+        Series::new(&column("plan_length"), plans.iter().map(|p| p.plan_length as u64).collect::<Vec<_>>()),
+        Series::new(&column("edge_removals"), plans.iter().map(|p| p.edge_removals as u64).collect::<Vec<_>>()),
+    ]
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_experiment<Alg, Env, Obs, Act, T>(
+    path: &dyn AsRef<Path>,
+    n_repetitions: usize,
+    init_env: ParamEnv<Env, Obs, Act>,
+    init_alg: ParamAlg<Alg>,
+    train_config: TrainConfig,
+    load_model: Option<(String, String)>,
+    pretrain_train_config: Option<TrainConfig>,
+    pretrain_env_config: Option<Env::Config>,
+    device: &Device,
+    telemetry: T,
+) -> Result<()>
+where
+    Env: Clone + Environment<Action = Act, Observation = Obs>,
+    Env::Config: Clone + Serialize,
+    Alg: Clone + Algorithm + OffPolicyAlgorithm + SaveableAlgorithm,
+    Alg::Config: Clone + Serialize,
+    Obs: Clone + TensorConvertible,
+    Act: Clone + TensorConvertible + Sampleable,
+    T: Fn(&Alg) -> Option<(GraphStats, PlanStats)>,
 {
     let path = Path::new("data/").join(path);
 
@@ -97,18 +254,31 @@ where
         write_config(&pretrain_env_config, path.join("config_pretraining_environment.ron"))?;
     }
 
-    for n in 0..n_repetitions {
-        warn!("Collecting data, run {n}/{n_repetitions}");
+    // Derive the seeds of every run up front, so they are recorded even if a run fails.
+    // The seeds of the pretraining and environment configs are replaced as well,
+    // otherwise every run would be pretrained on exactly the same episodes and see
+    // the same layouts.
+    let seeds = ExperimentSeeds {
+        seed: train_config.seed(),
+        runs: (0..n_repetitions).map(|n| RunSeeds::new(train_config.seed(), n)).collect(),
+    };
+    write_config(&seeds, path.join("seeds.ron"))?;
+
+    for (n, run_seeds) in seeds.runs.iter().enumerate() {
+        warn!("Collecting data, run {n}/{n_repetitions} with seeds {run_seeds:?}");
 
         // Create the Agent and the Environment
 
-        let mut env = *Env::new(env_config.clone()).unwrap();
+        let mut run_env_config = env_config.clone();
+        Env::seed_config(&mut run_env_config, run_seeds.environment);
+        let mut env = *Env::new(run_env_config).unwrap();
         let mut alg = *Alg::from_config(
             device,
             &alg_config,
             env.observation_space().iter().product::<usize>(),
             env.action_space().iter().product::<usize>(),
         ).unwrap();
+        alg.seed(run_seeds.algorithm)?;
 
         // Maybe load model weights
 
@@ -122,11 +292,16 @@ where
 
         // Maybe pretrain the Agent
 
-        if let Some(pretrain_train_config) = pretrain_train_config.clone() {
+        if let Some(mut pretrain_train_config) = pretrain_train_config.clone() {
+            pretrain_train_config.set_seed(run_seeds.pretraining);
 
             let (pretrain_mc_returns, _) = loop_off_policy(
                 &mut match pretrain_env_config {
-                    Some(ref env_config) => *Env::new(env_config.clone()).unwrap(),
+                    Some(ref env_config) => {
+                        let mut env_config = env_config.clone();
+                        Env::seed_config(&mut env_config, run_seeds.environment);
+                        *Env::new(env_config).unwrap()
+                    },
                     None => env.clone(),
                 },
                 &mut alg,
@@ -142,21 +317,28 @@ where
             warn!(
                 "Size of Replay Buffer: {:#?}",
                 alg.replay_buffer().size(),
-            )
+            );
+
+            alg.finish_pretraining();
         }
 
         // Train the Agent on the Environment
 
-        let (mc_returns, successes) = loop_off_policy(
+        let mut run_train_config = train_config.clone();
+        run_train_config.set_seed(run_seeds.training);
+
+        let mut episode_telemetry = Vec::new();
+        let (mc_returns, successes) = loop_off_policy_with_hook(
             &mut env,
             &mut alg,
-            train_config.clone(),
+            run_train_config,
             device,
+            |alg| episode_telemetry.extend(telemetry(alg)),
         )?;
 
         // Write collected data to file
 
-        let mut df = DataFrame::new(vec![
+        let mut columns = vec![
             Series::new(
                 &format!("run_{n}_total_rewards"),
                 &mc_returns,
@@ -165,7 +347,11 @@ where
                 &format!("run_{n}_successes"),
                 &successes,
             )
-        ])?;
+        ];
+        if !episode_telemetry.is_empty() {
+            columns.extend(telemetry_columns(n, &episode_telemetry));
+        }
+        let mut df = DataFrame::new(columns)?;
         ParquetWriter::new(
             File::create(path.join(format!("run_{n}_data.parquet")))?
         ).finish(&mut df)?;
@@ -113,6 +113,9 @@ where
                 if self.gui.render_buffer {
                     self.gui.render_buffer(plot_ui);
                 }
+                if self.gui.render_trajectories {
+                    self.gui.render_trajectories(plot_ui);
+                }
             });
         });
 
@@ -215,6 +218,7 @@ where
         ui.add(Checkbox::new(&mut self.render_graph, "Show Graph"));
         ui.add(Checkbox::new(&mut self.render_plan, "Show Plan"));
         ui.add(Checkbox::new(&mut self.gui.render_buffer, "Show Buffer"));
+        ui.add(Checkbox::new(&mut self.gui.render_trajectories, "Show Trajectories"));
     }
 }
 
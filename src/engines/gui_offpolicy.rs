@@ -21,12 +21,18 @@ use {
             RenderableConfig,
             TrainConfig,
         },
+        components::derive_seed,
         engines::RunMode,
     },
     anyhow::Result,
     tracing::warn,
     serde::Serialize,
     candle_core::Device,
+    rand::{
+        rngs::StdRng,
+        Rng,
+        SeedableRng,
+    },
     eframe::egui,
     egui::{
         widgets::{
@@ -82,7 +88,10 @@ where
     pub slowdown_ms: u64,
     pub slowdown_ticker: u64,
 
+    pub rng: StdRng,
+
     pub render_buffer: bool,
+    pub render_trajectories: bool,
 }
 
 impl<Alg, Env, Obs, Act> eframe::App for OffPolicyGUI<Alg, Env, Obs, Act>
@@ -122,6 +131,9 @@ where
                 if self.render_buffer {
                     self.render_buffer(plot_ui);
                 }
+                if self.render_trajectories {
+                    self.render_trajectories(plot_ui);
+                }
             });
         });
 
@@ -159,15 +171,17 @@ where
             },
         };
 
+        let seed = config.seed();
         let (mut alg, alg_config) = match &init_alg {
             ParamAlg::AsAlgorithm(alg) => (alg.clone(), alg.config().clone()),
             ParamAlg::AsConfig(config) => {
-                let alg = *Alg::from_config(
+                let mut alg = *Alg::from_config(
                     &device,
                     config,
                     env.observation_space().iter().product::<usize>(),
                     env.action_space().iter().product::<usize>(),
                 ).unwrap();
+                alg.seed(seed).unwrap();
                 (alg.clone(), alg.config().clone())
             },
         };
@@ -205,7 +219,9 @@ where
             warn!(
                 "Size of Replay Buffer: {:#?}",
                 alg.replay_buffer().size(),
-            )
+            );
+
+            alg.finish_pretraining();
         }
 
         Self {
@@ -222,7 +238,10 @@ where
             slowdown_ms: 0,
             slowdown_ticker: 0,
 
+            rng: StdRng::seed_from_u64(derive_seed(seed, "gui")),
+
             render_buffer: false,
+            render_trajectories: false,
         }
     }
 
@@ -270,6 +289,7 @@ where
                         &mut self.alg,
                         self.config.run_mode(),
                         &self.device,
+                        &mut self.rng,
                     )?;
                 }
                 PlayMode::Episodes => {
@@ -281,6 +301,7 @@ where
                             0,
                             0,
                             self.config.run_mode(),
+                            self.rng.gen::<u64>(),
                         ),
                         &self.device,
                     )?;
@@ -317,6 +338,28 @@ where
         }
     }
 
+    pub fn render_trajectories(
+        &self,
+        plot_ui: &mut PlotUi,
+    ) {
+        let buffer = self.alg.replay_buffer();
+        for episode in buffer.episodes() {
+            let points: Vec<[f64; 2]> = buffer
+                .trajectory_states::<Obs>(episode)
+                .into_iter()
+                .map(|state| {
+                    let s = <Obs>::to_vec(state);
+                    [s[0], s[1]]
+                })
+                .collect();
+            plot_ui.line(
+                Line::new(points)
+                    .width(1.0)
+                    .color(Color32::LIGHT_RED),
+            );
+        }
+    }
+
     pub fn render_returns(
         &mut self,
         plot_ui: &mut PlotUi,
@@ -391,6 +434,7 @@ where
                     size_state,
                     size_action,
                 ).unwrap();
+                self.alg.seed(self.config.seed()).unwrap();
                 self.run_data = Vec::new();
             };
             if ui.add(Button::new("Override Agent")).clicked() {
@@ -434,6 +478,7 @@ where
         ui.separator();
         ui.label("Render Options");
         ui.add(Checkbox::new(&mut self.render_buffer, "Show Buffer"));
+        ui.add(Checkbox::new(&mut self.render_trajectories, "Show Trajectories"));
     }
 }
 
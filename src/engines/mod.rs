@@ -11,7 +11,9 @@
 //! ## Training
 //!
 //! The training loop engines are used for training an agent on an environment
-//! once, for a number of episodes.
+//! once, for a number of episodes. The vectorized loop steps a batch of
+//! environments together and selects all their actions at once, while the
+//! parallel loop collects experience on worker threads during training.
 //!
 //! ## Testing
 //!
@@ -25,12 +27,21 @@
 
 mod experiment;
 mod run;
+mod parallel;
 mod tick;
 mod gui_offpolicy;
 mod gui_hgb;
 
-pub use experiment::run_experiment_off_policy;
-pub use run::loop_off_policy;
+pub use experiment::{
+    run_experiment_off_policy,
+    run_experiment_hgb,
+};
+pub use run::{
+    loop_off_policy,
+    loop_off_policy_with_hook,
+    loop_off_policy_vec,
+};
+pub use parallel::loop_off_policy_parallel;
 pub use tick::{tick, tick_off_policy};
 
 pub use gui_offpolicy::OffPolicyGUI;
@@ -0,0 +1,325 @@
+use {
+    crate::{
+        agents::{
+            Algorithm,
+            ParallelAlgorithm,
+        },
+        envs::{
+            Environment,
+            Sampleable,
+            TensorConvertible,
+            VectorConvertible,
+        },
+        configs::TrainConfig,
+        components::derive_seed,
+    },
+    super::RunMode,
+    anyhow::Result,
+    candle_core::{
+        Device,
+        Tensor,
+    },
+    rand::{
+        rngs::StdRng,
+        Rng,
+        SeedableRng,
+    },
+    std::{
+        sync::{
+            atomic::{
+                AtomicBool,
+                Ordering,
+            },
+            mpsc::{
+                channel,
+                sync_channel,
+                Receiver,
+                SyncSender,
+                TryRecvError,
+            },
+        },
+        thread,
+    },
+    tracing::warn,
+};
+
+type Weights = Vec<(String, Tensor)>;
+
+/// The number of messages per worker that can be queued for the learner before
+/// the workers have to wait for it, which bounds the memory when the learner
+/// falls behind.
+const QUEUED_MESSAGES_PER_WORKER: usize = 1000;
+
+/// A transition collected by a rollout worker.
+struct WorkerTransition {
+    state: Vec<f64>,
+    action: Vec<f64>,
+    reward: f64,
+    next_state: Vec<f64>,
+    terminated: bool,
+    truncated: bool,
+}
+
+/// A message from a rollout worker to the learner.
+enum WorkerMessage {
+    Transition(WorkerTransition),
+    EpisodeEnd {
+        total_reward: f64,
+        success: bool,
+    },
+}
+
+/// The settings a rollout worker needs besides its environment and algorithm.
+struct WorkerSetup {
+    id: usize,
+    seed: u64,
+    initial_random_actions: usize,
+    run_mode: RunMode,
+    weights: Weights,
+}
+
+/// Collect episodes with an own environment and a copy of the policy until
+/// `stop` is set or the learner hangs up, always acting with the latest
+/// weights that were broadcast by the learner.
+fn rollout_worker<Alg, Env, Obs, Act>(
+    env_config: Env::Config,
+    alg_config: Alg::Config,
+    setup: WorkerSetup,
+    transitions: SyncSender<(usize, WorkerMessage)>,
+    weights: Receiver<Weights>,
+    stop: &AtomicBool,
+) -> Result<()>
+where
+    Env: Environment<Action = Act, Observation = Obs>,
+    Alg: ParallelAlgorithm,
+    Obs: Clone + TensorConvertible,
+    Act: Clone + TensorConvertible + Sampleable,
+{
+    // the workers always act on the CPU
+    let device = &Device::Cpu;
+    let mut env = *Env::new(env_config)?;
+    let mut alg = *Alg::from_config(
+        device,
+        &alg_config,
+        env.observation_space().iter().product::<usize>(),
+        env.action_space().iter().product::<usize>(),
+    )?;
+    alg.seed(derive_seed(setup.seed, "algorithm"))?;
+    alg.set_policy_weights(&setup.weights)?;
+
+    let mut rng = StdRng::seed_from_u64(derive_seed(setup.seed, "loop"));
+    let mut steps_taken = 0;
+
+    while !stop.load(Ordering::Relaxed) {
+        let mut total_reward = 0.0;
+        env.reset(rng.gen::<u64>())?;
+
+        loop {
+            if let Some(latest) = weights.try_iter().last() {
+                alg.set_policy_weights(&latest)?;
+            }
+
+            let state = <Obs>::to_tensor(env.current_observation(), device)?;
+
+            // select an action, or randomly sample one
+            let action = if steps_taken < setup.initial_random_actions {
+                <Act>::to_tensor(<Act>::sample(&mut rng, &env.action_domain()), device)?
+            } else {
+                alg.actions(&state, setup.run_mode.clone())?
+            };
+
+            let step = env.step(<Act>::from_tensor_pp(action.clone()))?;
+            total_reward += step.reward;
+            steps_taken += 1;
+
+            let done = step.terminated || step.truncated;
+            let message = WorkerMessage::Transition(WorkerTransition {
+                state: state.to_vec1::<f64>()?,
+                action: action.to_vec1::<f64>()?,
+                reward: step.reward,
+                next_state: <Obs>::to_vec(step.observation),
+                terminated: step.terminated,
+                truncated: step.truncated,
+            });
+            if transitions.send((setup.id, message)).is_err() {
+                return Ok(());
+            }
+
+            if done {
+                alg.end_episode();
+                let message = WorkerMessage::EpisodeEnd {
+                    total_reward,
+                    success: step.terminated,
+                };
+                if transitions.send((setup.id, message)).is_err() {
+                    return Ok(());
+                }
+                break;
+            }
+            if stop.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Train a single run with an off-policy algorithm, using `n_workers` rollout
+/// worker threads that collect the experience in parallel to the training.
+///
+/// Every worker owns an environment created from `env_config` and an instance
+/// of the algorithm created from its config, and acts with a snapshot of the
+/// policy weights. The transitions are sent over a bounded channel to the
+/// learner on the calling thread, which trains `alg`, so the workers wait
+/// whenever the learner falls behind. The learner holds the transitions of
+/// every worker back until its episode has finished and then remembers them
+/// in order, so every episode is contiguous in the replay buffer as with
+/// [`loop_off_policy`](super::loop_off_policy). After every
+/// `sync_every` training iterations the learner broadcasts the updated policy
+/// weights to all workers.
+///
+/// The run ends once `config.max_episodes()` episodes have finished across all
+/// workers. As in [`loop_off_policy`](super::loop_off_policy), the learner
+/// trains for `config.training_iterations()` iterations per finished episode,
+/// but does so while the workers keep collecting. The iterations that are still
+/// due when the last episode has finished are run after the workers have
+/// stopped. The initial random actions are split between the workers.
+///
+/// # Arguments
+///
+/// * `env_config` - The configuration of the environments of the workers.
+/// * `alg` - The agent to train.
+/// * `n_workers` - The number of rollout worker threads.
+/// * `sync_every` - The number of training iterations between weight broadcasts.
+/// * `config` - The configuration for the algorithm.
+/// * `device` - The device the learner runs on.
+pub fn loop_off_policy_parallel<Alg, Env, Obs, Act>(
+    env_config: &Env::Config,
+    alg: &mut Alg,
+    n_workers: usize,
+    sync_every: usize,
+    config: TrainConfig,
+    device: &Device,
+) -> Result<(Vec<f64>, Vec<bool>)>
+where
+    Env: Environment<Action = Act, Observation = Obs>,
+    Env::Config: Clone + Send,
+    Alg: ParallelAlgorithm,
+    Alg::Config: Clone + Send,
+    Obs: Clone + TensorConvertible,
+    Act: Clone + TensorConvertible + Sampleable,
+{
+    if n_workers == 0 || sync_every == 0 {
+        return Err(anyhow::anyhow!("The number of workers and the sync interval must be positive"));
+    }
+    warn!("number of rollout workers: {n_workers}");
+
+    let mut mc_returns = Vec::new();
+    let mut successes = Vec::new();
+    let stop = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let (transition_sender, transitions) = sync_channel(n_workers * QUEUED_MESSAGES_PER_WORKER);
+        let mut weight_senders = Vec::new();
+        let mut workers = Vec::new();
+        let initial_weights = alg.policy_weights()?;
+
+        for i in 0..n_workers {
+            let (weight_sender, weights) = channel();
+            weight_senders.push(weight_sender);
+
+            let env_config = env_config.clone();
+            let alg_config = alg.config().clone();
+            let setup = WorkerSetup {
+                id: i,
+                seed: derive_seed(config.seed(), &format!("worker_{i}")),
+                initial_random_actions: config.initial_random_actions().div_ceil(n_workers),
+                run_mode: config.run_mode(),
+                weights: initial_weights.clone(),
+            };
+            let transition_sender = transition_sender.clone();
+            let stop = &stop;
+            workers.push(scope.spawn(move || {
+                rollout_worker::<Alg, Env, Obs, Act>(
+                    env_config,
+                    alg_config,
+                    setup,
+                    transition_sender,
+                    weights,
+                    stop,
+                )
+            }));
+        }
+        // only the workers hold senders, so a disconnect means they all stopped
+        drop(transition_sender);
+
+        let mut episodes: Vec<Vec<WorkerTransition>> = (0..n_workers).map(|_| Vec::new()).collect();
+        let mut iterations_due = 0;
+        let mut iterations_done = 0;
+        while mc_returns.len() < config.max_episodes() {
+            // train while there are iterations due, and wait for data otherwise
+            let message = if iterations_done < iterations_due {
+                match transitions.try_recv() {
+                    Ok(message) => Some(message),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => break,
+                }
+            } else {
+                match transitions.recv() {
+                    Ok(message) => Some(message),
+                    Err(_) => break,
+                }
+            };
+
+            match message {
+                Some((worker, WorkerMessage::Transition(transition))) => {
+                    episodes[worker].push(transition);
+                },
+                Some((worker, WorkerMessage::EpisodeEnd { total_reward, success })) => {
+                    for transition in episodes[worker].drain(..) {
+                        alg.remember(
+                            &Tensor::new(transition.state, device)?,
+                            &Tensor::new(transition.action, device)?,
+                            &Tensor::new(vec![transition.reward], device)?,
+                            &Tensor::new(transition.next_state, device)?,
+                            &Tensor::new(vec![transition.terminated as u8], device)?,
+                            &Tensor::new(vec![transition.truncated as u8], device)?,
+                        );
+                    }
+                    warn!("episode {} with total reward of {total_reward}", mc_returns.len());
+                    mc_returns.push(total_reward);
+                    successes.push(success);
+                    if let RunMode::Train = config.run_mode() {
+                        iterations_due += config.training_iterations();
+                    }
+                },
+                None => {
+                    alg.train()?;
+                    iterations_done += 1;
+                    if iterations_done % sync_every == 0 {
+                        let weights = alg.policy_weights()?;
+                        for sender in weight_senders.iter() {
+                            // a worker that has stopped no longer needs weights
+                            let _ = sender.send(weights.clone());
+                        }
+                    }
+                },
+            }
+        }
+
+        // dropping the receiver also releases workers that wait on a full channel
+        stop.store(true, Ordering::Relaxed);
+        drop(transitions);
+        for worker in workers {
+            worker.join().expect("A rollout worker panicked")?;
+        }
+
+        while iterations_done < iterations_due {
+            alg.train()?;
+            iterations_done += 1;
+        }
+        Ok::<(), anyhow::Error>(())
+    })?;
+
+    Ok((mc_returns, successes))
+}
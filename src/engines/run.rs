@@ -8,8 +8,10 @@ use {
             Environment,
             Sampleable,
             TensorConvertible,
+            VecEnv,
         },
         configs::TrainConfig,
+        components::derive_seed,
     },
     super::RunMode,
     anyhow::Result,
@@ -17,7 +19,11 @@ use {
         Device,
         Tensor,
     },
-    rand::Rng,
+    rand::{
+        rngs::StdRng,
+        Rng,
+        SeedableRng,
+    },
     tracing::warn,
 };
 
@@ -41,6 +47,37 @@ where
     Alg: Algorithm + OffPolicyAlgorithm,
     Obs: Clone + TensorConvertible,
     Act: Clone + TensorConvertible + Sampleable,
+{
+    loop_off_policy_with_hook(env, alg, config, device, |_| ())
+}
+
+/// Train a single run on an environment with an off-policy algorithm, and
+/// call `on_episode_end` with the agent after every episode.
+///
+/// The hook is called after the episode has finished but before the agent is
+/// trained, so it sees the agent in the same state it was in at the end of the
+/// episode.
+///
+/// # Arguments
+///
+/// * `env` - The environment to train on.
+/// * `alg` - The agent to train with.
+/// * `config` - The configuration for the algorithm.
+/// * `device` - The device to run on.
+/// * `on_episode_end` - The hook to call after each episode.
+pub fn loop_off_policy_with_hook<Alg, Env, Obs, Act, F>(
+    env: &mut Env,
+    alg: &mut Alg,
+    config: TrainConfig,
+    device: &Device,
+    mut on_episode_end: F,
+) -> Result<(Vec<f64>, Vec<bool>)>
+where
+    Env: Environment<Action = Act, Observation = Obs>,
+    Alg: Algorithm + OffPolicyAlgorithm,
+    Obs: Clone + TensorConvertible,
+    Act: Clone + TensorConvertible + Sampleable,
+    F: FnMut(&Alg),
 {
     warn!("action space: {:?}", env.action_space());
     warn!("observation space: {:?}", env.observation_space());
@@ -48,7 +85,8 @@ where
     let mut steps_taken = 0;
     let mut mc_returns = Vec::new();
     let mut successes = Vec::new();
-    let mut rng = rand::thread_rng();
+    // the reset seeds and random actions are derived from the master seed
+    let mut rng = StdRng::seed_from_u64(derive_seed(config.seed(), "loop"));
 
     for episode in 0..config.max_episodes() {
         let mut total_reward = 0.0;
@@ -85,6 +123,7 @@ where
 
         warn!("episode {episode} with total reward of {total_reward}");
         mc_returns.push(total_reward);
+        on_episode_end(alg);
 
         if let RunMode::Train = config.run_mode() {
             for _ in 0..config.training_iterations() {
@@ -93,4 +132,106 @@ where
         }
     }
     Ok((mc_returns, successes))
-}
\ No newline at end of file
+}
+
+/// Train a single run on a batch of environments with an off-policy algorithm.
+///
+/// All environments are stepped together, with the actions for the whole
+/// batch of observations selected by a single call to [`Algorithm::actions`],
+/// so the algorithm must accept batched states of shape `[N, ...]`. Finished
+/// environments are reset automatically by the [VecEnv].
+///
+/// The transitions of every environment are held back until its episode has
+/// finished and are then remembered in order, so every episode is contiguous
+/// in the replay buffer as with [loop_off_policy].
+///
+/// The run ends once `config.max_episodes()` episodes have finished across all
+/// environments, and the episodes that are still running then are dropped.
+/// After every finished episode the agent is trained for
+/// `config.training_iterations()` iterations, as in [loop_off_policy].
+///
+/// # Arguments
+///
+/// * `envs` - The environments to train on.
+/// * `alg` - The agent to train with.
+/// * `config` - The configuration for the algorithm.
+/// * `device` - The device to run on.
+pub fn loop_off_policy_vec<Alg, Env, Obs, Act>(
+    envs: &mut VecEnv<Env>,
+    alg: &mut Alg,
+    config: TrainConfig,
+    device: &Device,
+) -> Result<(Vec<f64>, Vec<bool>)>
+where
+    Env: Environment<Action = Act, Observation = Obs>,
+    Alg: Algorithm + OffPolicyAlgorithm,
+    Obs: Clone + TensorConvertible,
+    Act: Clone + TensorConvertible + Sampleable,
+{
+    warn!("number of environments: {}", envs.len());
+    warn!("action space: {:?}", envs.envs()[0].action_space());
+    warn!("observation space: {:?}", envs.envs()[0].observation_space());
+
+    let mut steps_taken = 0;
+    let mut mc_returns = Vec::new();
+    let mut successes = Vec::new();
+    let mut total_rewards = vec![0.0; envs.len()];
+    let mut episodes: Vec<Vec<[Tensor; 6]>> = vec![Vec::new(); envs.len()];
+    // the reset seeds and random actions are derived from the master seed
+    let mut rng = StdRng::seed_from_u64(derive_seed(config.seed(), "loop"));
+
+    envs.reset(&mut rng)?;
+
+    while mc_returns.len() < config.max_episodes() {
+        let states = envs.observations(device)?;
+
+        // select the actions, or randomly sample them
+        let actions = if steps_taken < config.initial_random_actions() {
+            let sampled = envs
+                .envs()
+                .iter()
+                .map(|env| <Act>::to_tensor(<Act>::sample(&mut rng, &env.action_domain()), device))
+                .collect::<candle_core::Result<Vec<_>>>()?;
+            Tensor::stack(&sampled, 0)?
+        } else {
+            alg.actions(&states, config.run_mode())?
+        };
+
+        let step = envs.step(&actions, &mut rng)?;
+        steps_taken += envs.len();
+
+        let mut finished = 0;
+        for (i, observation) in step.observations.iter().enumerate() {
+            total_rewards[i] += step.rewards[i];
+
+            episodes[i].push([
+                states.get(i)?,
+                actions.get(i)?,
+                Tensor::new(vec![step.rewards[i]], device)?,
+                <Obs>::to_tensor(observation.clone(), device)?,
+                Tensor::new(vec![step.terminated[i] as u8], device)?,
+                Tensor::new(vec![step.truncated[i] as u8], device)?,
+            ]);
+
+            if step.done(i) {
+                for [state, action, reward, next_state, terminated, truncated] in episodes[i].drain(..) {
+                    alg.remember(&state, &action, &reward, &next_state, &terminated, &truncated);
+                }
+                if mc_returns.len() < config.max_episodes() {
+                    warn!("episode {} with total reward of {}", mc_returns.len(), total_rewards[i]);
+                    mc_returns.push(total_rewards[i]);
+                    successes.push(step.terminated[i]);
+                    finished += 1;
+                }
+                total_rewards[i] = 0.0;
+            }
+        }
+
+        if let RunMode::Train = config.run_mode() {
+            for _ in 0..finished * config.training_iterations() {
+                alg.train()?;
+            }
+        }
+    }
+    Ok((mc_returns, successes))
+}
@@ -17,8 +17,8 @@ use {
         Tensor,
     },
     rand::{
-        thread_rng,
         Rng,
+        RngCore,
     },
 };
 
@@ -30,11 +30,13 @@ use {
 /// * `env` - The environment to step in.
 /// * `agent` - The agent to step with.
 /// * `device` - The device to run on.
+/// * `rng` - The random number generator for the reset seeds.
 pub fn tick<Alg, Env, Obs, Act>(
     env: &mut Env,
     agent: &mut Alg,
     mode: RunMode,
     device: &Device,
+    rng: &mut dyn RngCore,
 ) -> Result<()>
 where
     Env: Environment<Action = Act, Observation = Obs>,
@@ -47,7 +49,7 @@ where
     let step = env.step(<Act>::from_tensor_pp(action))?;
 
     if step.terminated || step.truncated {
-        env.reset(thread_rng().gen::<u64>())?;
+        env.reset(rng.gen::<u64>())?;
     }
 
     let x = (step.reward, step.terminated, step.truncated);
@@ -65,11 +67,13 @@ where
 /// * `env` - The environment to step in.
 /// * `agent` - The agent to step with.
 /// * `device` - The device to run on.
+/// * `rng` - The random number generator for the reset seeds.
 pub fn tick_off_policy<Alg, Env, Obs, Act>(
     env: &mut Env,
     agent: &mut Alg,
     mode: RunMode,
     device: &Device,
+    rng: &mut dyn RngCore,
 ) -> Result<()>
 where
     Env: Environment<Action = Act, Observation = Obs>,
@@ -91,7 +95,7 @@ where
     );
 
     if step.terminated || step.truncated {
-        env.reset(thread_rng().gen::<u64>())?;
+        env.reset(rng.gen::<u64>())?;
     }
 
     let x = (step.reward, step.terminated, step.truncated);
@@ -0,0 +1,388 @@
+use {
+    super::{
+        gym_wrappers::{
+            gym_make,
+            gym_reset,
+            gym_step,
+            GymKwarg,
+            GymSpace,
+        },
+        DistanceMeasure,
+        Environment,
+        RenderableEnvironment,
+        RecordableEnvironment,
+        Sampleable,
+        Step,
+        TensorConvertible,
+        VectorConvertible,
+    },
+    crate::configs::RenderableConfig,
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    anyhow::Result,
+    candle_core::{
+        Device,
+        Tensor,
+    },
+    egui_plot::{
+        Bar,
+        BarChart,
+        PlotUi,
+    },
+    ordered_float::OrderedFloat,
+    pyo3::PyObject,
+    rand::Rng,
+    std::{
+        collections::HashMap,
+        ops::RangeInclusive,
+    },
+};
+
+/// The configuration struct for the [GymEnv] environment
+///
+/// * `name` - The id the environment is registered with in gymnasium, e.g. `"Hopper-v4"`.
+/// * `kwargs` - The keyword arguments passed to `gymnasium.make`.
+/// * `timelimit` - The maximum number of steps per episode, passed to
+///   `gymnasium.make` as `max_episode_steps`. 0 uses the `max_episode_steps`
+///   the environment is registered with.
+/// * `value_range` - The range of the returns, which is not known in general.
+/// * `normalize_actions` - Whether actions in `[-1.0, 1.0]` are rescaled to
+///   the bounds of the action space.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GymConfig {
+    pub name: String,
+    pub kwargs: HashMap<String, GymKwarg>,
+    pub timelimit: usize,
+    pub value_range: (f64, f64),
+    pub normalize_actions: bool,
+}
+impl Default for GymConfig {
+    fn default() -> Self {
+        Self {
+            name: "Pendulum-v1".to_owned(),
+            kwargs: HashMap::new(),
+            timelimit: 0,
+            value_range: (-3300.0, 1300.0),
+            normalize_actions: true,
+        }
+    }
+}
+impl GymConfig {
+    pub fn new(
+        name: &str,
+        kwargs: HashMap<String, GymKwarg>,
+        timelimit: usize,
+        value_range: (f64, f64),
+        normalize_actions: bool,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            kwargs,
+            timelimit,
+            value_range,
+            normalize_actions,
+        }
+    }
+}
+impl RenderableConfig for GymConfig {
+    fn render_immutable(
+        &self,
+        ui: &mut egui::Ui,
+    ) {
+        ui.label("GymEnv");
+        ui.label(format!("name: {}", self.name));
+        let mut kwargs = self.kwargs.iter().collect::<Vec<_>>();
+        kwargs.sort_by(|a, b| a.0.cmp(b.0));
+        for (key, value) in kwargs {
+            ui.label(format!("{key}: {value:?}"));
+        }
+        ui.label(format!("timelimit: {}", self.timelimit));
+        ui.label(format!("value_range: {:?}", self.value_range));
+        ui.label(format!("normalize_actions: {}", self.normalize_actions));
+    }
+
+    fn render_mutable(
+        &mut self,
+        ui: &mut egui::Ui,
+    ) {
+        self.render_immutable(ui);
+    }
+}
+
+/// The action type for the [GymEnv] environment, the flattened action space.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GymAction(pub Vec<OrderedFloat<f64>>);
+impl Sampleable for GymAction {
+    /// Sample a random [GymAction] uniformly from the domain.
+    ///
+    /// Unbounded ranges are sampled from `[-1.0, 1.0]` instead.
+    fn sample(
+        rng: &mut dyn rand::RngCore,
+        domain: &[RangeInclusive<f64>],
+    ) -> Self {
+        Self(
+            domain
+                .iter()
+                .map(|range| {
+                    let lo = if range.start().is_finite() { *range.start() } else { -1.0 };
+                    let hi = if range.end().is_finite() { *range.end() } else { 1.0 };
+                    OrderedFloat(rng.gen_range(lo..=hi))
+                })
+                .collect(),
+        )
+    }
+}
+impl VectorConvertible for GymAction {
+    /// There is no general preprocessing, see `normalize_actions` of [GymConfig].
+    fn from_vec_pp(value: Vec<f64>) -> Self {
+        Self::from_vec(value)
+    }
+    fn from_vec(value: Vec<f64>) -> Self {
+        Self(value.into_iter().map(OrderedFloat).collect())
+    }
+    fn to_vec(value: Self) -> Vec<f64> {
+        value.0.into_iter().map(|v| *v).collect()
+    }
+}
+impl TensorConvertible for GymAction {
+    fn from_tensor_pp(value: Tensor) -> Self {
+        Self::from_vec_pp(value.to_vec1::<f64>().unwrap())
+    }
+    fn from_tensor(value: Tensor) -> Self {
+        Self::from_vec(value.to_vec1::<f64>().unwrap())
+    }
+    fn to_tensor(
+        value: Self,
+        device: &Device,
+    ) -> candle_core::Result<Tensor> {
+        Tensor::new(Self::to_vec(value), device)
+    }
+}
+
+/// The observation type for the [GymEnv] environment, the flattened
+/// observation space.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GymObservation(pub Vec<OrderedFloat<f64>>);
+impl VectorConvertible for GymObservation {
+    /// There is no general preprocessing, wrap the environment in a
+    /// [`NormalizeObservation`](super::wrappers::NormalizeObservation) instead.
+    fn from_vec_pp(value: Vec<f64>) -> Self {
+        Self::from_vec(value)
+    }
+    fn from_vec(value: Vec<f64>) -> Self {
+        Self(value.into_iter().map(OrderedFloat).collect())
+    }
+    fn to_vec(value: Self) -> Vec<f64> {
+        value.0.into_iter().map(|v| *v).collect()
+    }
+}
+impl TensorConvertible for GymObservation {
+    fn from_tensor_pp(value: Tensor) -> Self {
+        Self::from_vec_pp(value.to_vec1::<f64>().unwrap())
+    }
+    fn from_tensor(value: Tensor) -> Self {
+        Self::from_vec(value.to_vec1::<f64>().unwrap())
+    }
+    fn to_tensor(
+        value: Self,
+        device: &Device,
+    ) -> candle_core::Result<Tensor> {
+        Tensor::new(Self::to_vec(value), device)
+    }
+}
+impl DistanceMeasure for GymObservation {
+    /// The Euclidean distance between the flattened observations.
+    fn distance(
+        s1: &Self,
+        s2: &Self,
+    ) -> f64 {
+        s1.0.iter()
+            .zip(s2.0.iter())
+            .map(|(a, b)| (**a - **b).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+/// A wrapper around any Gymnasium environment with `Box`, `Discrete` or
+/// `Dict` spaces.
+///
+/// The environment is created with `gymnasium.make(name, **kwargs)`, and the
+/// spaces are flattened into vectors of `f64` values, with the bounds of the
+/// spaces as their domains. `Discrete` actions are rounded to the nearest
+/// valid value.
+#[derive(Clone)]
+pub struct GymEnv {
+    config: GymConfig,
+    env: PyObject,
+    timelimit: usize,
+    current_observation: GymObservation,
+    action_space: GymSpace,
+    observation_space: GymSpace,
+}
+impl GymEnv {
+    /// Rescale an action from `[-1.0, 1.0]` to the bounds of the action
+    /// space, leaving values of unbounded ranges as they are.
+    fn rescale_action(
+        &self,
+        action: Vec<f64>,
+    ) -> Vec<f64> {
+        action
+            .into_iter()
+            .zip(self.action_space.domain())
+            .map(|(value, range)| {
+                let (lo, hi) = (*range.start(), *range.end());
+                if lo.is_finite() && hi.is_finite() {
+                    lo + (value.clamp(-1.0, 1.0) + 1.0) * 0.5 * (hi - lo)
+                } else {
+                    value
+                }
+            })
+            .collect()
+    }
+}
+
+impl Environment for GymEnv {
+    type Config = GymConfig;
+    type Action = GymAction;
+    type Observation = GymObservation;
+
+    /// Create a new [GymEnv] with the given [GymConfig]
+    ///
+    /// This fails if the Gymnasium environment cannot be created, has
+    /// unsupported spaces or no timelimit is known.
+    fn new(config: Self::Config) -> Result<Box<Self>> {
+        // gymnasium truncates the episodes with its TimeLimit wrapper
+        let mut kwargs = config.kwargs.clone();
+        if config.timelimit > 0 {
+            kwargs.insert("max_episode_steps".to_owned(), GymKwarg::Int(config.timelimit as i64));
+        }
+        let (env, action_space, observation_space, max_episode_steps) = gym_make(&config.name, &kwargs)?;
+        let timelimit = match (config.timelimit, max_episode_steps) {
+            (0, Some(steps)) => steps,
+            (0, None) => return Err(anyhow::anyhow!("{} has no max_episode_steps, set a timelimit", config.name)),
+            (timelimit, _) => timelimit,
+        };
+        let current_observation = GymObservation::from_vec(gym_reset(&env, 0, &observation_space)?);
+        Ok(Box::new(Self {
+            config,
+            env,
+            timelimit,
+            current_observation,
+            action_space,
+            observation_space,
+        }))
+    }
+
+    /// Reset the environment with the given seed
+    fn reset(
+        &mut self,
+        seed: u64,
+    ) -> Result<Self::Observation> {
+        self.current_observation = GymObservation::from_vec(gym_reset(&self.env, seed, &self.observation_space)?);
+        Ok(self.current_observation())
+    }
+
+    /// Step the environment with the given action
+    ///
+    /// With `normalize_actions`, the action is rescaled to the bounds of the
+    /// action space before it is passed to gymnasium. The returned [Step]
+    /// contains the action as it was given.
+    fn step(
+        &mut self,
+        action: Self::Action,
+    ) -> Result<Step<Self::Observation, Self::Action>> {
+        let values = GymAction::to_vec(action.clone());
+        let values = if self.config.normalize_actions {
+            self.rescale_action(values)
+        } else {
+            values
+        };
+        let (observation, reward, terminated, truncated) =
+            gym_step(&self.env, &values, &self.action_space, &self.observation_space)?;
+        self.current_observation = GymObservation::from_vec(observation);
+        Ok(Step {
+            observation: self.current_observation(),
+            action,
+            reward,
+            terminated,
+            truncated,
+        })
+    }
+
+    /// Return the maximum number of steps allowed before the episode is truncated.
+    fn timelimit(&self) -> usize {
+        self.timelimit
+    }
+
+    /// The action space is the size of the flattened action space.
+    fn action_space(&self) -> Vec<usize> {
+        vec![self.action_space.size()]
+    }
+
+    /// The action domain are the bounds of the action space, or `[-1.0, 1.0]`
+    /// for every bounded value with `normalize_actions`.
+    fn action_domain(&self) -> Vec<RangeInclusive<f64>> {
+        let domain = self.action_space.domain();
+        if self.config.normalize_actions {
+            domain
+                .into_iter()
+                .map(|range| {
+                    if range.start().is_finite() && range.end().is_finite() {
+                        -1.0..=1.0
+                    } else {
+                        range
+                    }
+                })
+                .collect()
+        } else {
+            domain
+        }
+    }
+
+    /// The observation space is the size of the flattened observation space.
+    fn observation_space(&self) -> Vec<usize> {
+        vec![self.observation_space.size()]
+    }
+
+    /// The observation domain are the bounds of the observation space.
+    fn observation_domain(&self) -> Vec<RangeInclusive<f64>> {
+        self.observation_space.domain()
+    }
+
+    /// Return the current observation of the [GymEnv]
+    fn current_observation(&self) -> Self::Observation {
+        self.current_observation.clone()
+    }
+
+    /// Return the value range set in the [GymConfig].
+    fn value_range(&self) -> (f64, f64) {
+        self.config.value_range
+    }
+
+    /// Return the [GymConfig] used to create this [GymEnv]
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+}
+
+impl RenderableEnvironment for GymEnv {
+    /// Draw the values of the current observation as bars.
+    fn render(
+        &mut self,
+        plot_ui: &mut PlotUi,
+    ) {
+        let bars = self
+            .current_observation
+            .0
+            .iter()
+            .enumerate()
+            .map(|(i, value)| Bar::new(i as f64, **value))
+            .collect();
+        plot_ui.bar_chart(BarChart::new(bars).name("observation"));
+    }
+}
+
+impl RecordableEnvironment for GymEnv {}
@@ -8,6 +8,7 @@ use {
         DistanceMeasure,
         Environment,
         RenderableEnvironment,
+        RecordableEnvironment,
         Sampleable,
         Step,
         TensorConvertible,
@@ -31,7 +32,10 @@ use {
     ordered_float::OrderedFloat,
     pyo3::PyObject,
     rand::Rng,
-    std::ops::RangeInclusive,
+    std::{
+        collections::HashMap,
+        ops::RangeInclusive,
+    },
 };
 
 fn preprocess_action(mut value: Vec<f64>) -> Vec<f64> {
@@ -259,7 +263,7 @@ impl Environment for PendulumEnv {
     ///
     /// This function panics if the Gymnasium environment cannot be created.
     fn new(config: Self::Config) -> Result<Box<Self>> {
-        let (env, action_space, observation_space) = gym_create_env(&config.name, false)?;
+        let (env, action_space, observation_space) = gym_create_env(&config.name, &HashMap::new(), false)?;
         Ok(Box::new(Self {
             config: config.clone(),
             env,
@@ -366,3 +370,5 @@ impl RenderableEnvironment for PendulumEnv {
         )
     }
 }
+
+impl RecordableEnvironment for PendulumEnv {}
@@ -4,10 +4,12 @@ use {
             gym_create_env,
             gym_reset_env,
             gym_step_env,
+            GymKwarg,
         },
         DistanceMeasure,
         Environment,
         RenderableEnvironment,
+        RecordableEnvironment,
         Sampleable,
         Step,
         TensorConvertible,
@@ -34,7 +36,10 @@ use {
     ordered_float::OrderedFloat,
     pyo3::PyObject,
     rand::Rng,
-    std::ops::RangeInclusive,
+    std::{
+        collections::HashMap,
+        ops::RangeInclusive,
+    },
 };
 
 fn preprocess_action(mut value: Vec<f64>) -> Vec<f64> {
@@ -70,19 +75,16 @@ pub struct PointMazeEnv {
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct PointMazeConfig {
-    name: String,
-    maze: Vec<Vec<char>>,
-    width: usize,
-    height: usize,
-    timelimit: usize,
-    reward_mode: PointMazeReward,
-    seed: u64,
+    pub(super) name: String,
+    pub(super) maze: Vec<Vec<char>>,
+    pub(super) width: usize,
+    pub(super) height: usize,
+    pub(super) timelimit: usize,
+    pub(super) reward_mode: PointMazeReward,
+    pub(super) seed: u64,
 }
 impl Default for PointMazeConfig {
     fn default() -> Self {
-        // TODO: this hardcodes the PointMaze_Open-v3
-        // adapt gym_create_env to take some kwargs
-        // and parse the env string with its options
         let name = "PointMaze_OpenDense-v3".to_owned();
         let maze = vec![
             vec!['1', '1', '1', '1', '1', '1', '1'],
@@ -106,6 +108,58 @@ impl Default for PointMazeConfig {
         }
     }
 }
+impl PointMazeConfig {
+    /// Create a new [PointMazeConfig] for the given maze.
+    ///
+    /// The maze is a grid of characters where `'1'` is a wall, `'0'` is free,
+    /// `'g'` is a goal cell, `'r'` is a reset cell and `'c'` is both.
+    /// The `name` is only used by the gymnasium backed [PointMazeEnv].
+    pub fn new(
+        name: String,
+        maze: Vec<Vec<char>>,
+        timelimit: usize,
+        reward_mode: PointMazeReward,
+        seed: u64,
+    ) -> Self {
+        let width = maze.first().map_or(0, |row| row.len());
+        let height = maze.len();
+        Self {
+            name,
+            maze,
+            width,
+            height,
+            timelimit,
+            reward_mode,
+            seed,
+        }
+    }
+
+    pub fn maze(&self) -> &Vec<Vec<char>> {
+        &self.maze
+    }
+
+    /// The kwargs that make gymnasium build the configured maze, where walls
+    /// and free cells are passed as `1` and `0` and the other cells as
+    /// strings.
+    fn kwargs(&self) -> HashMap<String, GymKwarg> {
+        let maze_map = self
+            .maze
+            .iter()
+            .map(|row| {
+                GymKwarg::List(
+                    row.iter()
+                        .map(|cell| match cell {
+                            '0' => GymKwarg::Int(0),
+                            '1' => GymKwarg::Int(1),
+                            cell => GymKwarg::Str(cell.to_string()),
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+        HashMap::from([("maze_map".to_owned(), GymKwarg::List(maze_map))])
+    }
+}
 impl RenderableConfig for PointMazeConfig {
     fn render_immutable(
         &self,
@@ -128,7 +182,6 @@ impl RenderableConfig for PointMazeConfig {
     }
 }
 
-#[allow(dead_code)]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum PointMazeReward {
     SparseNegative,
@@ -226,6 +279,15 @@ impl VectorConvertible for PointMazeState {
         vec![*value.x, *value.y]
     }
 }
+impl PointMazeState {
+    pub fn x(&self) -> f64 {
+        self.x.into_inner()
+    }
+
+    pub fn y(&self) -> f64 {
+        self.y.into_inner()
+    }
+}
 impl TensorConvertible for PointMazeState {
     fn from_tensor_pp(value: Tensor) -> Self {
         Self::from_vec_pp(value.to_vec1::<f64>().unwrap())
@@ -408,8 +470,15 @@ impl Environment for PointMazeEnv {
     type Action = PointMazeAction;
     type Observation = PointMazeObservation;
 
+    fn seed_config(
+        config: &mut Self::Config,
+        seed: u64,
+    ) {
+        config.seed = seed;
+    }
+
     fn new(config: Self::Config) -> Result<Box<Self>> {
-        let (env, action_space, observation_space) = gym_create_env(&config.name, true)?;
+        let (env, action_space, observation_space) = gym_create_env(&config.name, &config.kwargs(), true)?;
         let current_observation = gym_reset_env(&env, config.seed, true)?;
         Ok(Box::new(Self {
             config: config.clone(),
@@ -551,3 +620,5 @@ impl RenderableEnvironment for PointMazeEnv {
         );
     }
 }
+
+impl RecordableEnvironment for PointMazeEnv {}
@@ -1,6 +1,9 @@
 //! Wrappers around the Python API of Gymnasium (the new version of OpenAI gym)
 use {
-    std::collections::HashMap,
+    std::{
+        collections::HashMap,
+        ops::RangeInclusive,
+    },
     super::{
         Step,
         VectorConvertible,
@@ -10,20 +13,196 @@ use {
         Error,
         Result,
     },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
     pyo3::{
         Python,
         PyErr,
         PyResult,
         PyAny,
         PyObject,
+        ToPyObject,
         types::{
             PyDict,
             PyTuple,
         },
-        exceptions::PyKeyError,
+        exceptions::{
+            PyKeyError,
+            PyTypeError,
+        },
     },
 };
 
+/// A keyword argument that is passed to `gymnasium.make`.
+///
+/// In a RON config the variant is inferred from the value, so the kwargs of
+/// a [`GymConfig`](super::GymConfig) can be written as
+/// `{"continuing_task": true, "max_episode_steps": 500}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GymKwarg {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    List(Vec<GymKwarg>),
+}
+impl ToPyObject for GymKwarg {
+    fn to_object(
+        &self,
+        py: Python<'_>,
+    ) -> PyObject {
+        match self {
+            Self::Bool(value) => value.to_object(py),
+            Self::Int(value) => value.to_object(py),
+            Self::Float(value) => value.to_object(py),
+            Self::Str(value) => value.to_object(py),
+            Self::List(value) => value.to_object(py),
+        }
+    }
+}
+
+/// The supported `gymnasium.spaces`, with their bounds.
+///
+/// Every space is flattened into a vector of `f64` values: a `Box` into its
+/// elements in row-major order, a `Discrete` into a single value and a `Dict`
+/// into the concatenation of its subspaces, in the order of the keys of the
+/// space.
+#[derive(Debug, Clone)]
+pub enum GymSpace {
+    Box {
+        shape: Vec<usize>,
+        low: Vec<f64>,
+        high: Vec<f64>,
+    },
+    Discrete {
+        n: i64,
+        start: i64,
+    },
+    Dict(Vec<(String, GymSpace)>),
+}
+impl GymSpace {
+    /// Parse a space of the Python API.
+    pub fn parse(
+        py: Python,
+        space: &PyAny,
+    ) -> PyResult<Self> {
+        let spaces = py.import("gymnasium")?.getattr("spaces")?;
+        if space.is_instance(spaces.getattr("Box")?)? {
+            Ok(Self::Box {
+                shape: space.getattr("shape")?.extract()?,
+                low: flatten(py, space.getattr("low")?)?,
+                high: flatten(py, space.getattr("high")?)?,
+            })
+        } else if space.is_instance(spaces.getattr("Discrete")?)? {
+            Ok(Self::Discrete {
+                n: space.getattr("n")?.extract()?,
+                start: space.getattr("start")?.extract()?,
+            })
+        } else if space.is_instance(spaces.getattr("Dict")?)? {
+            let subspaces = space.getattr("spaces")?.downcast::<PyDict>()?;
+            let mut parsed = Vec::new();
+            for (key, subspace) in subspaces.iter() {
+                parsed.push((key.extract::<String>()?, Self::parse(py, subspace)?));
+            }
+            Ok(Self::Dict(parsed))
+        } else {
+            Err(PyTypeError::new_err(format!("Unsupported space: {space}")))
+        }
+    }
+
+    /// The number of values of the flattened space.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Box { low, .. } => low.len(),
+            Self::Discrete { .. } => 1,
+            Self::Dict(subspaces) => subspaces.iter().map(|(_, s)| s.size()).sum(),
+        }
+    }
+
+    /// The range of every value of the flattened space. Unbounded values of a
+    /// `Box` have infinite ranges.
+    pub fn domain(&self) -> Vec<RangeInclusive<f64>> {
+        match self {
+            Self::Box { low, high, .. } => low.iter().zip(high).map(|(lo, hi)| *lo..=*hi).collect(),
+            Self::Discrete { n, start } => vec![*start as f64..=(start + n - 1) as f64],
+            Self::Dict(subspaces) => subspaces.iter().flat_map(|(_, s)| s.domain()).collect(),
+        }
+    }
+
+    /// Flatten a value of this space.
+    fn flatten_value(
+        &self,
+        py: Python,
+        value: &PyAny,
+    ) -> PyResult<Vec<f64>> {
+        match self {
+            Self::Box { .. } => flatten(py, value),
+            Self::Discrete { .. } => Ok(vec![value.extract::<i64>()? as f64]),
+            Self::Dict(subspaces) => {
+                let value = value.downcast::<PyDict>()?;
+                let mut values = Vec::with_capacity(self.size());
+                for (key, subspace) in subspaces {
+                    let item = value
+                        .get_item(key)?
+                        .ok_or_else(|| pyerr(&format!("No {key}!"), py))?;
+                    values.extend(subspace.flatten_value(py, item)?);
+                }
+                Ok(values)
+            },
+        }
+    }
+
+    /// Build a value of this space from its flattened values. `Discrete`
+    /// values are rounded and clamped to the valid range.
+    fn unflatten_value(
+        &self,
+        py: Python,
+        values: &[f64],
+    ) -> PyResult<PyObject> {
+        match self {
+            Self::Box { shape, .. } => {
+                let array = py
+                    .import("numpy")?
+                    .getattr("asarray")?
+                    .call1((values.to_vec(), "float64"))?;
+                Ok(array.call_method1("reshape", (shape.clone(),))?.into())
+            },
+            Self::Discrete { n, start } => {
+                let value = (values[0].round() as i64).clamp(*start, start + n - 1);
+                Ok(value.to_object(py))
+            },
+            Self::Dict(_) => Err(PyTypeError::new_err("Dict actions are not supported")),
+        }
+    }
+}
+
+/// Flatten an array-like value to a vector in row-major order.
+fn flatten(
+    py: Python,
+    value: &PyAny,
+) -> PyResult<Vec<f64>> {
+    py.import("numpy")?
+        .getattr("asarray")?
+        .call1((value, "float64"))?
+        .call_method1("reshape", (-1,))?
+        .call_method0("tolist")?
+        .extract()
+}
+
+fn make_kwargs<'py>(
+    py: Python<'py>,
+    kwargs: &HashMap<String, GymKwarg>,
+) -> PyResult<&'py PyDict> {
+    let dict = PyDict::new(py);
+    for (key, value) in kwargs.iter() {
+        dict.set_item(key, value)?;
+    }
+    Ok(dict)
+}
+
 fn w(res: PyErr) -> Error {
     anyhow!(res)
 }
@@ -54,6 +233,7 @@ where
 
 pub fn gym_create_env(
     name: &str,
+    kwargs: &HashMap<String, GymKwarg>,
     goal_aware: bool,
 ) -> Result<(PyObject, Vec<usize>, Vec<usize>)> {
     Python::with_gil(|py| {
@@ -64,7 +244,7 @@ pub fn gym_create_env(
         println!("PYTHON EXECUTABLE: {path}");
         let gym = py.import("gymnasium")?;
         let make = gym.getattr("make")?;
-        let env = make.call1((name,))?;
+        let env = make.call((name,), Some(make_kwargs(py, kwargs)?))?;
 
         let action_space = env.getattr("action_space")?;
         let action_space = action_space.getattr("shape")?.extract()?;
@@ -135,3 +315,65 @@ where
         truncated,
     })
 }
+
+/// Create a gymnasium environment with the given kwargs, and parse its action
+/// and observation spaces.
+///
+/// Also returns the `max_episode_steps` of the registered environment, if
+/// there is one.
+pub fn gym_make(
+    name: &str,
+    kwargs: &HashMap<String, GymKwarg>,
+) -> Result<(PyObject, GymSpace, GymSpace, Option<usize>)> {
+    Python::with_gil(|py| {
+        let gym = py.import("gymnasium")?;
+        let env = gym.getattr("make")?.call((name,), Some(make_kwargs(py, kwargs)?))?;
+        let action_space = GymSpace::parse(py, env.getattr("action_space")?)?;
+        let observation_space = GymSpace::parse(py, env.getattr("observation_space")?)?;
+        let spec = env.getattr("spec")?;
+        let max_episode_steps = if spec.is_none() {
+            None
+        } else {
+            spec.getattr("max_episode_steps")?.extract()?
+        };
+        Ok((env.into(), action_space, observation_space, max_episode_steps))
+    })
+    .map_err(w)
+}
+
+/// Reset a gymnasium environment, and flatten the observation according to
+/// the `observation_space`.
+pub fn gym_reset(
+    env: &PyObject,
+    seed: u64,
+    observation_space: &GymSpace,
+) -> Result<Vec<f64>> {
+    Python::with_gil(|py| {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("seed", seed)?;
+        let observation = env.call_method(py, "reset", (), Some(kwargs))?;
+        observation_space.flatten_value(py, observation.as_ref(py).get_item(0)?)
+    })
+    .map_err(w)
+}
+
+/// Step a gymnasium environment with a flattened action, and return the
+/// flattened observation, the reward and the terminated and truncated flags.
+pub fn gym_step(
+    env: &PyObject,
+    action: &[f64],
+    action_space: &GymSpace,
+    observation_space: &GymSpace,
+) -> Result<(Vec<f64>, f64, bool, bool)> {
+    Python::with_gil(|py| {
+        let action = action_space.unflatten_value(py, action)?;
+        let step = env.call_method(py, "step", (action, ), None)?;
+        let step = step.as_ref(py);
+        let observation = observation_space.flatten_value(py, step.get_item(0)?)?;
+        let reward: f64 = step.get_item(1)?.extract()?;
+        let terminated: bool = step.get_item(2)?.extract()?;
+        let truncated: bool = step.get_item(3)?.extract()?;
+        Ok((observation, reward, terminated, truncated))
+    })
+    .map_err(w)
+}
@@ -1,7 +1,13 @@
+mod gym_env;
 mod gym_pendulum;
 mod gym_pointmaze;
 mod gym_wrappers;
+mod native_pointmaze;
 mod pointenv;
+mod vec_env;
+
+pub mod remote;
+pub mod wrappers;
 
 use {
     anyhow::Result,
@@ -15,23 +21,54 @@ use {
 };
 
 pub use crate::envs::{
+    gym_env::{
+        GymAction,
+        GymConfig,
+        GymEnv,
+        GymObservation,
+    },
+    gym_wrappers::GymKwarg,
     gym_pendulum::{
         PendulumConfig,
         PendulumEnv,
     },
     gym_pointmaze::{
+        PointMazeAction,
         PointMazeConfig,
         PointMazeEnv,
+        PointMazeObservation,
+        PointMazeReward,
+        PointMazeState,
     },
+    native_pointmaze::NativePointMazeEnv,
     pointenv::{
         config::{
             PointEnvConfig,
             PointEnvWalls,
         },
+        maze::{
+            MazeAlgorithm,
+            MazeConfig,
+        },
+        image::{
+            PointImage,
+            IMAGE_CHANNELS,
+        },
+        lidar::PointLidar,
+        obstacle::PointObstacle,
         point_env::PointEnv,
+        dynamics::PointRegion,
+        dynamic_wall::{
+            PointDynamicWall,
+            PointWallSchedule,
+        },
         reward::PointReward,
         line::PointLine,
     },
+    vec_env::{
+        VecEnv,
+        VecStep,
+    },
 };
 
 pub trait TensorConvertible: VectorConvertible {
@@ -97,6 +134,16 @@ pub trait Environment {
 
     fn config(&self) -> &Self::Config;
     fn new(config: Self::Config) -> Result<Box<Self>>;
+
+    /// Replace the seed in a config of the environment, e.g. for every
+    /// repetition of an experiment. Configs without a seed are left as they are.
+    fn seed_config(
+        config: &mut Self::Config,
+        seed: u64,
+    ) {
+        let _ = (config, seed);
+    }
+
     fn reset(
         &mut self,
         seed: u64,
@@ -120,3 +167,12 @@ pub trait RenderableEnvironment {
         plot_ui: &mut PlotUi,
     );
 }
+
+pub trait RecordableEnvironment {
+    /// Environment specific values describing the current step, by column
+    /// name, which are recorded next to the observations by
+    /// [`RecordEpisodes`](wrappers::RecordEpisodes).
+    fn step_info(&self) -> Vec<(String, f64)> {
+        Vec::new()
+    }
+}
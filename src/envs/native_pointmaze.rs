@@ -0,0 +1,444 @@
+use {
+    super::{
+        gym_pointmaze::{
+            PointMazeAction,
+            PointMazeConfig,
+            PointMazeObservation,
+            PointMazeReward,
+        },
+        Environment,
+        GoalAwareObservation,
+        RenderableEnvironment,
+        RecordableEnvironment,
+        Step,
+        VectorConvertible,
+    },
+    anyhow::{
+        bail,
+        Result,
+    },
+    egui::Color32,
+    egui_plot::{
+        Line,
+        PlotBounds,
+        PlotUi,
+        Points,
+    },
+    rand::{
+        rngs::StdRng,
+        seq::SliceRandom,
+        Rng,
+        SeedableRng,
+    },
+    std::ops::RangeInclusive,
+    tracing::info,
+};
+
+// Simulation constants, chosen to roughly match the MuJoCo PointMaze
+// (unit sized cells, a small ball and clipped velocities).
+const BALL_RADIUS: f64 = 0.1;
+const GOAL_THRESHOLD: f64 = 0.45;
+const POSITION_NOISE: f64 = 0.25;
+const MAX_VELOCITY: f64 = 5.0;
+const ACCELERATION: f64 = 10.0;
+const DT: f64 = 0.1;
+const SUBSTEPS: usize = 10;
+
+/// The cell `(row, column)` is a wall.
+fn is_wall(
+    maze: &[Vec<char>],
+    row: isize,
+    col: isize,
+) -> bool {
+    if row < 0 || col < 0 {
+        return true;
+    }
+    match maze.get(row as usize).and_then(|r| r.get(col as usize)) {
+        Some(cell) => *cell == '1',
+        None => true,
+    }
+}
+
+/// Build the wall segments of the maze, i.e. every edge between a wall cell and
+/// a free cell, in the same coordinates as gymnasium-robotics (centered at 0).
+fn maze_walls(maze: &[Vec<char>]) -> Vec<((f64, f64), (f64, f64))> {
+    let height = maze.len() as f64;
+    let width = maze.first().map_or(0, |row| row.len()) as f64;
+    let mut walls = Vec::new();
+    for (i, row) in maze.iter().enumerate() {
+        for (j, _) in row.iter().enumerate() {
+            let (i, j) = (i as isize, j as isize);
+            if is_wall(maze, i, j) {
+                continue;
+            }
+            let left = j as f64 - width / 2.0;
+            let right = left + 1.0;
+            let top = height / 2.0 - i as f64;
+            let bottom = top - 1.0;
+            if is_wall(maze, i - 1, j) {
+                walls.push(((left, top), (right, top)));
+            }
+            if is_wall(maze, i + 1, j) {
+                walls.push(((left, bottom), (right, bottom)));
+            }
+            if is_wall(maze, i, j - 1) {
+                walls.push(((left, bottom), (left, top)));
+            }
+            if is_wall(maze, i, j + 1) {
+                walls.push(((right, bottom), (right, top)));
+            }
+        }
+    }
+    walls
+}
+
+/// A native point-mass maze that mirrors the gymnasium-robotics PointMaze.
+///
+/// The wall geometry is built from the [PointMazeConfig] character grid, so
+/// it runs without an embedded Python interpreter. Cells are unit sized and the
+/// maze is centered at the origin. A ball is pushed around by a 2D force with
+/// clipped velocities and stops at the walls. Goals are sampled from `'g'` cells
+/// and resets from `'r'` cells (`'c'` is both), falling back to all free cells.
+#[derive(Clone)]
+pub struct NativePointMazeEnv {
+    config: PointMazeConfig,
+    maze: Vec<Vec<char>>,
+    walls: Vec<((f64, f64), (f64, f64))>,
+    width: usize,
+    height: usize,
+    timelimit: usize,
+    timestep: usize,
+    reward_mode: PointMazeReward,
+    position: (f64, f64),
+    velocity: (f64, f64),
+    goal: (f64, f64),
+    history: Vec<(f64, f64)>,
+    rng: StdRng,
+}
+
+impl NativePointMazeEnv {
+    /// The center of the cell `(row, column)`.
+    fn cell_center(
+        &self,
+        row: usize,
+        col: usize,
+    ) -> (f64, f64) {
+        (
+            (col as f64 + 0.5) - self.width as f64 / 2.0,
+            self.height as f64 / 2.0 - (row as f64 + 0.5),
+        )
+    }
+
+    /// The cells that are marked with any of the given characters, or all
+    /// free cells if there are none.
+    fn cells(
+        &self,
+        marks: &[char],
+    ) -> Vec<(usize, usize)> {
+        let cells = |pred: &dyn Fn(char) -> bool| {
+            self.maze
+                .iter()
+                .enumerate()
+                .flat_map(|(i, row)| {
+                    row.iter()
+                        .enumerate()
+                        .filter(|(_, c)| pred(**c))
+                        .map(move |(j, _)| (i, j))
+                })
+                .collect::<Vec<_>>()
+        };
+        let marked = cells(&|c| marks.contains(&c));
+        if marked.is_empty() {
+            cells(&|c| c != '1')
+        } else {
+            marked
+        }
+    }
+
+    /// Sample a position uniformly around the center of the given cell.
+    fn sample_in_cell(
+        &mut self,
+        (row, col): (usize, usize),
+    ) -> (f64, f64) {
+        let (x, y) = self.cell_center(row, col);
+        (
+            x + self.rng.gen_range(-POSITION_NOISE..=POSITION_NOISE),
+            y + self.rng.gen_range(-POSITION_NOISE..=POSITION_NOISE),
+        )
+    }
+
+    /// The ball placed at `(x, y)` overlaps a wall cell.
+    fn collides(
+        &self,
+        (x, y): (f64, f64),
+    ) -> bool {
+        let col = (x + self.width as f64 / 2.0).floor() as isize;
+        let row = (self.height as f64 / 2.0 - y).floor() as isize;
+        for i in (row - 1)..=(row + 1) {
+            for j in (col - 1)..=(col + 1) {
+                if !is_wall(&self.maze, i, j) {
+                    continue;
+                }
+                let left = j as f64 - self.width as f64 / 2.0;
+                let top = self.height as f64 / 2.0 - i as f64;
+                let closest_x = x.clamp(left, left + 1.0);
+                let closest_y = y.clamp(top - 1.0, top);
+                if (x - closest_x).powi(2) + (y - closest_y).powi(2) < BALL_RADIUS.powi(2) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn distance_to_goal(&self) -> f64 {
+        ((self.position.0 - self.goal.0).powi(2) + (self.position.1 - self.goal.1).powi(2)).sqrt()
+    }
+
+    pub fn walls(&self) -> &Vec<((f64, f64), (f64, f64))> {
+        &self.walls
+    }
+
+    pub fn history(&self) -> &Vec<(f64, f64)> {
+        &self.history
+    }
+}
+
+impl Environment for NativePointMazeEnv {
+    type Config = PointMazeConfig;
+    type Action = PointMazeAction;
+    type Observation = PointMazeObservation;
+
+    fn seed_config(
+        config: &mut Self::Config,
+        seed: u64,
+    ) {
+        config.seed = seed;
+    }
+
+    fn new(config: Self::Config) -> Result<Box<Self>> {
+        let maze = config.maze.clone();
+        let height = maze.len();
+        let width = maze.first().map_or(0, |row| row.len());
+        if height == 0 || width == 0 {
+            bail!("The maze must not be empty");
+        }
+        if maze.iter().any(|row| row.len() != width) {
+            bail!("All rows of the maze must have the same length");
+        }
+        if let Some(c) = maze.iter().flatten().find(|c| !['0', '1', 'g', 'r', 'c'].contains(c)) {
+            bail!("Unknown maze cell {c:?}, expected one of '0', '1', 'g', 'r' or 'c'");
+        }
+        if maze.iter().flatten().all(|c| *c == '1') {
+            bail!("The maze must contain at least one free cell");
+        }
+
+        let mut env = Self {
+            walls: maze_walls(&maze),
+            maze,
+            width,
+            height,
+            timelimit: config.timelimit,
+            timestep: 0,
+            reward_mode: config.reward_mode.clone(),
+            position: (0.0, 0.0),
+            velocity: (0.0, 0.0),
+            goal: (0.0, 0.0),
+            history: Vec::new(),
+            rng: StdRng::seed_from_u64(config.seed),
+            config: config.clone(),
+        };
+        env.reset(config.seed)?;
+        Ok(Box::new(env))
+    }
+
+    /// Sample a new goal and start, both uniformly around the center of a
+    /// goal / reset cell. The start is never placed in the goal cell if
+    /// there is any other option.
+    fn reset(
+        &mut self,
+        seed: u64,
+    ) -> Result<Self::Observation> {
+        self.rng = StdRng::seed_from_u64(seed);
+
+        let goal_cell = *self.cells(&['g', 'c']).choose(&mut self.rng).unwrap();
+        let mut reset_cells = self.cells(&['r', 'c']);
+        if reset_cells.len() > 1 {
+            reset_cells.retain(|cell| *cell != goal_cell);
+        }
+        let reset_cell = *reset_cells.choose(&mut self.rng).unwrap();
+
+        self.goal = self.sample_in_cell(goal_cell);
+        self.position = self.sample_in_cell(reset_cell);
+        self.velocity = (0.0, 0.0);
+        self.timestep = 0;
+        self.history = vec![self.position];
+
+        Ok(self.current_observation())
+    }
+
+    fn step(
+        &mut self,
+        action: Self::Action,
+    ) -> Result<Step<Self::Observation, Self::Action>> {
+        // Clamp the force to the action domain
+        let action = PointMazeAction::from_vec_pp(PointMazeAction::to_vec(action));
+        let force = PointMazeAction::to_vec(action.clone());
+        self.timestep += 1;
+
+        // Integrate the dynamics in substeps and resolve collisions per axis
+        let sub_dt = DT / SUBSTEPS as f64;
+        for _ in 0..SUBSTEPS {
+            self.velocity.0 = (self.velocity.0 + force[0] * ACCELERATION * sub_dt)
+                .clamp(-MAX_VELOCITY, MAX_VELOCITY);
+            self.velocity.1 = (self.velocity.1 + force[1] * ACCELERATION * sub_dt)
+                .clamp(-MAX_VELOCITY, MAX_VELOCITY);
+
+            let next = (self.position.0 + self.velocity.0 * sub_dt, self.position.1);
+            if self.collides(next) {
+                self.velocity.0 = 0.0;
+            } else {
+                self.position = next;
+            }
+            let next = (self.position.0, self.position.1 + self.velocity.1 * sub_dt);
+            if self.collides(next) {
+                self.velocity.1 = 0.0;
+            } else {
+                self.position = next;
+            }
+        }
+        self.history.push(self.position);
+
+        let distance = self.distance_to_goal();
+        let terminated = distance <= GOAL_THRESHOLD;
+        let truncated = !terminated && (self.timestep >= self.timelimit);
+        let reward = match self.reward_mode {
+            PointMazeReward::Dense => -distance,
+            PointMazeReward::Sparse => if terminated { 1.0 } else { 0.0 },
+            PointMazeReward::SparseNegative => -1.0,
+        };
+
+        info!(
+            concat!(
+                "\nNativePointMaze Step:",
+                "\nS({:.3}, {:.3}) + G({:.3}, {:.3})",
+                "\nR: {:?}",
+            ),
+            self.position.0,
+            self.position.1,
+            self.goal.0,
+            self.goal.1,
+            reward,
+        );
+
+        Ok(Step {
+            observation: self.current_observation(),
+            action,
+            reward,
+            terminated,
+            truncated,
+        })
+    }
+
+    fn timelimit(&self) -> usize {
+        self.timelimit
+    }
+
+    fn action_space(&self) -> Vec<usize> {
+        vec![2]
+    }
+
+    fn action_domain(&self) -> Vec<RangeInclusive<f64>> {
+        vec![-1.0..=1.0, -1.0..=1.0]
+    }
+
+    fn observation_space(&self) -> Vec<usize> {
+        vec![8]
+    }
+
+    fn observation_domain(&self) -> Vec<RangeInclusive<f64>> {
+        let (half_w, half_h) = (self.width as f64 / 2.0, self.height as f64 / 2.0);
+        vec![
+            -half_w..=half_w,
+            -half_h..=half_h,
+            -MAX_VELOCITY..=MAX_VELOCITY,
+            -MAX_VELOCITY..=MAX_VELOCITY,
+            -half_w..=half_w,
+            -half_h..=half_h,
+            -half_w..=half_w,
+            -half_h..=half_h,
+        ]
+    }
+
+    /// The observation has the same layout as the gymnasium PointMaze:
+    /// `[x, y, vx, vy, goal_x, goal_y, x, y]`.
+    fn current_observation(&self) -> Self::Observation {
+        PointMazeObservation::from_vec(vec![
+            self.position.0,
+            self.position.1,
+            self.velocity.0,
+            self.velocity.1,
+            self.goal.0,
+            self.goal.1,
+            self.position.0,
+            self.position.1,
+        ])
+    }
+
+    fn value_range(&self) -> (f64, f64) {
+        let (lo, hi) = self
+            .reward_mode
+            .value_range(self.timelimit, self.width, self.height);
+
+        // add 40% padding to upper bound
+        let padding = (lo.abs() + hi.abs()) * 0.4;
+
+        (lo, hi + padding)
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+}
+
+impl RenderableEnvironment for NativePointMazeEnv {
+    fn render(
+        &mut self,
+        plot_ui: &mut PlotUi,
+    ) {
+        // Setup plot bounds
+        let (half_w, half_h) = (self.width as f64 / 2.0, self.height as f64 / 2.0);
+        plot_ui.set_plot_bounds(PlotBounds::from_min_max([-half_w, -half_h], [half_w, half_h]));
+        // Plot walls
+        for ((x1, y1), (x2, y2)) in self.walls.iter() {
+            plot_ui.line(
+                Line::new(vec![[*x1, *y1], [*x2, *y2]])
+                    .width(3.0)
+                    .color(Color32::WHITE),
+            )
+        }
+        // Plot state and goal
+        let obs = self.current_observation();
+        let state = obs.achieved_goal();
+        let goal = obs.desired_goal();
+        plot_ui.points(
+            Points::new(vec![[goal.x(), goal.y()]])
+                .radius(2.0)
+                .color(Color32::GREEN),
+        );
+        plot_ui.points(
+            Points::new(vec![[state.x(), state.y()]])
+                .radius(2.0)
+                .color(Color32::RED),
+        );
+        // Plot path
+        plot_ui.line(Line::new(
+            self.history
+                .iter()
+                .map(|(x, y)| [*x, *y])
+                .collect::<Vec<_>>(),
+        ));
+    }
+}
+
+impl RecordableEnvironment for NativePointMazeEnv {}
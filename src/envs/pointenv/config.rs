@@ -1,8 +1,16 @@
 use {
     super::{
+        dynamic_wall::PointDynamicWall,
+        dynamics::PointRegion,
         line::PointLine,
+        maze::{
+            MazeAlgorithm,
+            MazeConfig,
+        },
+        obstacle::PointObstacle,
         reward::PointReward,
     },
+    crate::components::derive_seed,
     crate::configs::RenderableConfig,
     strum::{
         EnumIter,
@@ -23,12 +31,43 @@ use {
         Slider,
         ComboBox,
     },
-    std::fmt::Display,
-    anyhow::Result,
+    std::{
+        fmt::Display,
+        fs,
+        path::{
+            Path,
+            PathBuf,
+        },
+    },
+    anyhow::{
+        anyhow,
+        Context,
+        Result,
+    },
+    tracing::warn,
 };
 
+/// The characters that mark a wall cell in an ASCII-art grid.
+const GRID_WALL_CHARS: [char; 2] = ['#', '1'];
+
 /// An enum representing the different wall configurations for the
 /// [`PointEnv`](super::point_env::PointEnv) environment.
+///
+/// Besides the predefined layouts, walls can be given explicitly:
+/// * `Custom` - A list of `((x1, y1), (x2, y2))` segments in env coordinates.
+/// * `Grid` - An ASCII-art grid where `'#'` or `'1'` marks a wall cell. The grid
+///   is scaled to the `width` and `height` of the environment, and the first row
+///   is the top of the environment.
+/// * `Maze` - A procedurally generated maze, see [`MazeConfig`].
+/// * `File` - Walls loaded from a file when the environment is created, in any
+///   of the formats of [`PointEnvWalls::from_file`].
+///
+/// In a RON config these look like:
+/// ```text
+/// walls: Custom([((0.0, 5.0), (8.0, 5.0))]),
+/// walls: Grid(["....", ".##.", "...."]),
+/// walls: File("walls.grid"),
+/// ```
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize, EnumIter)]
 pub enum PointEnvWalls {
     None,
@@ -36,6 +75,10 @@ pub enum PointEnvWalls {
     TwoLine,
     FourLine,
     Hooks,
+    Custom(Vec<((f64, f64), (f64, f64))>),
+    Grid(Vec<String>),
+    Maze(MazeConfig),
+    File(PathBuf),
 }
 impl Display for PointEnvWalls {
     fn fmt(
@@ -48,14 +91,171 @@ impl Display for PointEnvWalls {
             Self::TwoLine => write!(f, "TwoLine"),
             Self::FourLine => write!(f, "FourLine"),
             Self::Hooks => write!(f, "Hooks"),
+            Self::Custom(walls) => write!(f, "Custom ({} segments)", walls.len()),
+            Self::Grid(rows) => write!(f, "Grid ({} rows)", rows.len()),
+            Self::Maze(maze) => write!(f, "Maze ({}x{} {})", maze.cols, maze.rows, maze.algorithm),
+            Self::File(path) => write!(f, "File ({})", path.display()),
         }
     }
 }
 impl PointEnvWalls {
+    /// Load walls from a file, based on its extension:
+    /// * `.json` - A list of segments, e.g. `[[[0.0, 5.0], [8.0, 5.0]]]`.
+    /// * `.grid` - An ASCII-art grid, see [`PointEnvWalls::Grid`].
+    /// * Anything else - One segment `x1 y1 x2 y2` per line, separated by
+    ///   whitespace or commas. Empty lines and lines starting with `#` are skipped.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read walls from {}", path.display()))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(Self::Custom(serde_json::from_str(&content)?)),
+            Some("grid") => Ok(Self::Grid(
+                content
+                    .lines()
+                    .map(|line| line.trim_end().to_owned())
+                    .filter(|line| !line.is_empty())
+                    .collect(),
+            )),
+            _ => Self::from_segments(&content),
+        }
+    }
+
+    /// Parse walls given as one `x1 y1 x2 y2` segment per line.
+    pub fn from_segments(content: &str) -> Result<Self> {
+        let mut walls = Vec::new();
+        for (n, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let values = line
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|value| !value.is_empty())
+                .map(|value| value.parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .with_context(|| format!("Invalid number on line {}", n + 1))?;
+            if values.len() != 4 {
+                return Err(anyhow!("Expected 4 values on line {} but got {}", n + 1, values.len()));
+            }
+            walls.push(((values[0], values[1]), (values[2], values[3])));
+        }
+        Ok(Self::Custom(walls))
+    }
+
+    /// Convert an ASCII-art grid into wall segments along the borders between
+    /// wall and free cells, scaled to `width` and `height`.
+    ///
+    /// Consecutive cell edges on the same line are merged into one segment.
+    fn grid_segments(
+        rows: &[String],
+        width: f64,
+        height: f64,
+    ) -> Vec<((f64, f64), (f64, f64))> {
+        let grid = rows
+            .iter()
+            .map(|row| row.chars().map(|c| GRID_WALL_CHARS.contains(&c)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let n_rows = grid.len();
+        let n_cols = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+        if n_rows == 0 || n_cols == 0 {
+            return Vec::new();
+        }
+        let cell_w = width / n_cols as f64;
+        let cell_h = height / n_rows as f64;
+        let is_wall = |i: isize, j: isize| -> bool {
+            i >= 0 && j >= 0 && grid
+                .get(i as usize)
+                .and_then(|row| row.get(j as usize))
+                .copied()
+                .unwrap_or(false)
+        };
+
+        let mut segments = Vec::new();
+        // Horizontal edges lie on row boundaries `i` (between rows i-1 and i)
+        for i in 0..=n_rows as isize {
+            let y = height - i as f64 * cell_h;
+            let mut start = None;
+            for j in 0..=n_cols as isize {
+                let edge = j < n_cols as isize && is_wall(i - 1, j) != is_wall(i, j);
+                match (edge, start) {
+                    (true, None) => start = Some(j),
+                    (false, Some(s)) => {
+                        segments.push(((s as f64 * cell_w, y), (j as f64 * cell_w, y)));
+                        start = None;
+                    },
+                    _ => (),
+                }
+            }
+        }
+        // Vertical edges lie on column boundaries `j` (between columns j-1 and j)
+        for j in 0..=n_cols as isize {
+            let x = j as f64 * cell_w;
+            let mut start = None;
+            for i in 0..=n_rows as isize {
+                let edge = i < n_rows as isize && is_wall(i, j - 1) != is_wall(i, j);
+                match (edge, start) {
+                    (true, None) => start = Some(i),
+                    (false, Some(s)) => {
+                        segments.push(((x, height - s as f64 * cell_h), (x, height - i as f64 * cell_h)));
+                        start = None;
+                    },
+                    _ => (),
+                }
+            }
+        }
+        segments
+    }
+
+    /// Check that the walls are valid for an environment of the given size.
+    pub fn check(
+        &self,
+        width: f64,
+        height: f64,
+    ) -> Result<()> {
+        match self {
+            Self::Custom(walls) => {
+                for (n, ((x1, y1), (x2, y2))) in walls.iter().enumerate() {
+                    if ![x1, y1, x2, y2].iter().all(|v| v.is_finite()) {
+                        return Err(anyhow!("Wall {n} has non-finite coordinates"));
+                    }
+                    if x1 == x2 && y1 == y2 {
+                        return Err(anyhow!("Wall {n} is degenerate (both endpoints are equal)"));
+                    }
+                    if ![x1, x2].iter().all(|x| (0.0..=width).contains(*x))
+                        || ![y1, y2].iter().all(|y| (0.0..=height).contains(*y))
+                    {
+                        return Err(anyhow!("Wall {n} lies outside of the environment bounds"));
+                    }
+                }
+            },
+            Self::Grid(rows) => {
+                if rows.is_empty() || rows.iter().all(|row| row.is_empty()) {
+                    return Err(anyhow!("Wall grid must not be empty"));
+                }
+                let n_cols = rows[0].chars().count();
+                if rows.iter().any(|row| row.chars().count() != n_cols) {
+                    return Err(anyhow!("All rows of the wall grid must have the same length"));
+                }
+            },
+            Self::Maze(maze) => maze.check()?,
+            Self::File(path) => Self::from_file(path)?.check(width, height)?,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Convert the layout into [PointLine]s, including the outer walls.
+    ///
+    /// The seed is only used by generated layouts such as [`PointEnvWalls::Maze`].
+    /// A [`PointEnvWalls::File`] that cannot be read is logged and gives no
+    /// walls besides the outer ones, since [`PointEnvWalls::check`] already
+    /// reports it when the environment is created.
     pub fn to_walls(
         &self,
         width: f64,
         height: f64,
+        seed: u64,
     ) -> Vec<PointLine> {
         let mut walls = match self {
             Self::None => Vec::new(),
@@ -79,6 +279,23 @@ impl PointEnvWalls {
                 PointLine::from(((width * 0.3, height * 0.8), (width, height * 0.8))),
                 PointLine::from(((width * 0.3, height * 0.8), (width * 0.3, height * 0.4))),
             ],
+            Self::Custom(walls) => walls
+                .iter()
+                .map(|&wall| PointLine::from(wall))
+                .collect(),
+            Self::Grid(rows) => Self::grid_segments(rows, width, height)
+                .into_iter()
+                .map(PointLine::from)
+                .collect(),
+            Self::Maze(maze) => maze.generate(width, height, derive_seed(seed, "maze")),
+            // the loaded walls add the outer walls themselves
+            Self::File(path) => match Self::from_file(path) {
+                Ok(walls) => return walls.to_walls(width, height, seed),
+                Err(e) => {
+                    warn!("{e:#}, using no walls");
+                    Vec::new()
+                },
+            },
         };
         walls.extend([
             PointLine::from(((0.0, 0.0), (width, 0.0))),
@@ -103,6 +320,8 @@ impl PointEnvWalls {
 /// * `width` - The width of the environment.
 /// * `height` - The height of the environment.
 /// * `walls` - The walls of the environment given as a Vec of [`PointLines`](super::line::PointLine)
+/// * `obstacles` - Solid [`PointObstacles`](super::obstacle::PointObstacle) that the agent cannot enter.
+/// * `dynamic_walls` - Doors and walls that open and close over time, see [`PointDynamicWall`](super::dynamic_wall::PointDynamicWall).
 /// * `timelimit` - The maximum number of steps before the episode is truncated.
 /// * `step_radius` - The radius that defines the maximum distance the agent can reach in one step.
 /// * `term_radius` - If the agent is within this radius of the goal, the episode is terminated.
@@ -110,6 +329,13 @@ impl PointEnvWalls {
 /// * `spawn_radius_max` - The maximum distance allowed between the randomly generated start and goal.
 /// * `spawn_centers` - When these points are given, then spawn start and goal with set radius around those points.
 /// * `bounce_factor` - The percentage of the traveled distance that the agent bounces back when it hits a wall.
+/// * `bounce_noise` - The bounce factor is sampled uniformly within this fraction of `bounce_factor` (0 disables it).
+/// * `action_noise` - The standard deviation of Gaussian noise added to every action (0 disables it).
+/// * `action_dropout` - The probability that an action is dropped and the agent does not move (0 disables it).
+/// * `regions` - [`PointRegions`](super::dynamics::PointRegion) with wind or slippery ground that perturb motion.
+/// * `image_resolution` - Add a top-down image of this many pixels per side to the observations (0 disables it).
+/// * `lidar_rays` - Add the readings of a range sensor with this many rays to the observations (0 disables it).
+/// * `lidar_range` - The maximum distance the range sensor can measure.
 /// * `reward` - The reward function. For more information, see [`PointReward`](super::reward::PointReward)
 /// * `seed` - The seed for the random number generator.
 ///
@@ -137,6 +363,8 @@ pub struct PointEnvConfig {
     pub width: f64,
     pub height: f64,
     pub walls: PointEnvWalls,
+    pub obstacles: Vec<PointObstacle>,
+    pub dynamic_walls: Vec<PointDynamicWall>,
     pub timelimit: usize,
     pub step_radius: f64,
     pub term_radius: f64,
@@ -144,6 +372,13 @@ pub struct PointEnvConfig {
     pub spawn_radius_min: Option<f64>,
     pub spawn_centers: Option<((f64, f64), (f64, f64))>,
     pub bounce_factor: f64,
+    pub bounce_noise: f64,
+    pub action_noise: f64,
+    pub action_dropout: f64,
+    pub regions: Vec<PointRegion>,
+    pub image_resolution: usize,
+    pub lidar_rays: usize,
+    pub lidar_range: f64,
     pub reward: PointReward,
     pub seed: u64,
 }
@@ -153,6 +388,8 @@ impl Default for PointEnvConfig {
             width: 10.0,
             height: 10.0,
             walls: PointEnvWalls::None,
+            obstacles: Vec::new(),
+            dynamic_walls: Vec::new(),
             timelimit: 100,
             step_radius: 1.0,
             term_radius: 0.5,
@@ -160,6 +397,13 @@ impl Default for PointEnvConfig {
             spawn_radius_min: None,
             spawn_centers: None,
             bounce_factor: 0.1,
+            bounce_noise: 0.0,
+            action_noise: 0.0,
+            action_dropout: 0.0,
+            regions: Vec::new(),
+            image_resolution: 0,
+            lidar_rays: 0,
+            lidar_range: 2.0,
             reward: PointReward::Sparse,
             seed: StdRng::from_entropy().gen::<u64>(),
         }
@@ -172,6 +416,8 @@ impl PointEnvConfig {
         width: f64,
         height: f64,
         walls: PointEnvWalls,
+        obstacles: Vec<PointObstacle>,
+        dynamic_walls: Vec<PointDynamicWall>,
         timelimit: usize,
         step_radius: f64,
         term_radius: f64,
@@ -179,6 +425,13 @@ impl PointEnvConfig {
         spawn_radius_min: Option<f64>,
         spawn_centers: Option<((f64, f64), (f64, f64))>,
         bounce_factor: f64,
+        bounce_noise: f64,
+        action_noise: f64,
+        action_dropout: f64,
+        regions: Vec<PointRegion>,
+        image_resolution: usize,
+        lidar_rays: usize,
+        lidar_range: f64,
         reward: PointReward,
         seed: u64,
     ) -> Self {
@@ -186,6 +439,8 @@ impl PointEnvConfig {
             width,
             height,
             walls,
+            obstacles,
+            dynamic_walls,
             timelimit,
             step_radius,
             term_radius,
@@ -193,12 +448,46 @@ impl PointEnvConfig {
             spawn_radius_min,
             spawn_centers,
             bounce_factor,
+            bounce_noise,
+            action_noise,
+            action_dropout,
+            regions,
+            image_resolution,
+            lidar_rays,
+            lidar_range,
             reward,
             seed,
         }
     }
 
     pub fn check(&self) -> Result<()> {
+        self.walls.check(self.width, self.height)?;
+        for obstacle in self.obstacles.iter() {
+            obstacle.check()?;
+        }
+        for region in self.regions.iter() {
+            region.check()?;
+        }
+        for dynamic_wall in self.dynamic_walls.iter() {
+            dynamic_wall.check()?;
+        }
+
+        if !(0.0..1.0).contains(&self.bounce_noise) {
+            return Err(anyhow::anyhow!("Bounce noise must be in the range [0.0, 1.0)"));
+        }
+
+        if !(self.action_noise >= 0.0 && self.action_noise.is_finite()) {
+            return Err(anyhow::anyhow!("Action noise must be a finite non-negative number"));
+        }
+
+        if self.lidar_rays > 0 && !(self.lidar_range > 0.0 && self.lidar_range.is_finite()) {
+            return Err(anyhow::anyhow!("Lidar range must be a finite positive number"));
+        }
+
+        if !(0.0..=1.0).contains(&self.action_dropout) {
+            return Err(anyhow::anyhow!("Action dropout must be in the range [0.0, 1.0]"));
+        }
+
         if !(self.step_radius > 0.0 && self.step_radius <= 1.0) {
             return Err(anyhow::anyhow!("Step radius must be in the range (0.0, 1.0]"));
         }
@@ -241,6 +530,8 @@ impl RenderableConfig for PointEnvConfig {
         let width = self.width;
         let height = self.height;
         let walls = &self.walls;
+        let n_obstacles = self.obstacles.len();
+        let n_dynamic_walls = self.dynamic_walls.len();
         let timelimit = self.timelimit;
         let step_radius = self.step_radius;
         let term_radius = self.term_radius;
@@ -248,6 +539,13 @@ impl RenderableConfig for PointEnvConfig {
         let spawn_radius_min = self.spawn_radius_min;
         let spawn_centers = self.spawn_centers;
         let bounce_factor = self.bounce_factor;
+        let bounce_noise = self.bounce_noise;
+        let action_noise = self.action_noise;
+        let action_dropout = self.action_dropout;
+        let n_regions = self.regions.len();
+        let image_resolution = self.image_resolution;
+        let lidar_rays = self.lidar_rays;
+        let lidar_range = self.lidar_range;
         let reward = &self.reward;
         let seed = self.seed;
 
@@ -256,6 +554,8 @@ impl RenderableConfig for PointEnvConfig {
         ui.add(Label::new(format!("Width: {width:#.2}")));
         ui.add(Label::new(format!("Height: {height:#.2}")));
         ui.add(Label::new(format!("Walls: {walls:#}")));
+        ui.add(Label::new(format!("Obstacles: {n_obstacles}")));
+        ui.add(Label::new(format!("Dynamic walls: {n_dynamic_walls}")));
         ui.add(Label::new(format!("Timelimit: {timelimit:#.2}")));
         ui.add(Label::new(format!("Step radius: {step_radius:#.2}")));
         ui.add(Label::new(format!("Term radius: {term_radius:#.2}")));
@@ -267,6 +567,13 @@ impl RenderableConfig for PointEnvConfig {
         }
         ui.add(Label::new(format!("Spawn centers: {spawn_centers:?}")));
         ui.add(Label::new(format!("Bounce factor: {bounce_factor:#.2}")));
+        ui.add(Label::new(format!("Bounce noise: {bounce_noise:#.2}")));
+        ui.add(Label::new(format!("Action noise: {action_noise:#.2}")));
+        ui.add(Label::new(format!("Action dropout: {action_dropout:#.2}")));
+        ui.add(Label::new(format!("Regions: {n_regions}")));
+        ui.add(Label::new(format!("Image resolution: {image_resolution}")));
+        ui.add(Label::new(format!("Lidar rays: {lidar_rays}")));
+        ui.add(Label::new(format!("Lidar range: {lidar_range:#.2}")));
         ui.add(Label::new(format!("Reward: {reward:?}")));
         ui.add(Label::new(format!("Seed: {seed:#.2}")));
     }
@@ -291,6 +598,18 @@ impl RenderableConfig for PointEnvConfig {
             .selected_text(format!("{}", self.walls))
             .show_ui(ui, |ui| {
                 for wall in PointEnvWalls::iter() {
+                    // Explicit walls can only be set from a config or file,
+                    // so only offer them when they are the current selection
+                    let wall = match (&wall, &self.walls) {
+                        (PointEnvWalls::Custom(_), PointEnvWalls::Custom(_))
+                        | (PointEnvWalls::Grid(_), PointEnvWalls::Grid(_))
+                        | (PointEnvWalls::File(_), PointEnvWalls::File(_)) => self.walls.clone(),
+                        (PointEnvWalls::Custom(_), _)
+                        | (PointEnvWalls::Grid(_), _)
+                        | (PointEnvWalls::File(_), _) => continue,
+                        (PointEnvWalls::Maze(_), PointEnvWalls::Maze(_)) => self.walls.clone(),
+                        _ => wall,
+                    };
                     ui.selectable_value(
                         &mut self.walls,
                         wall.clone(),
@@ -299,6 +618,37 @@ impl RenderableConfig for PointEnvConfig {
                 }
             }
         );
+        if let PointEnvWalls::Maze(maze) = &mut self.walls {
+            ui.add(
+                Slider::new(&mut maze.rows, 1..=20)
+                .text("Maze rows")
+            );
+            ui.add(
+                Slider::new(&mut maze.cols, 1..=20)
+                .text("Maze cols")
+            );
+            ComboBox::from_label("Maze algorithm")
+                .selected_text(format!("{}", maze.algorithm))
+                .show_ui(ui, |ui| {
+                    for algorithm in MazeAlgorithm::iter() {
+                        ui.selectable_value(
+                            &mut maze.algorithm,
+                            algorithm,
+                            format!("{}", algorithm),
+                        );
+                    }
+                }
+            );
+            ui.add(
+                Slider::new(&mut maze.braid, 0.0..=1.0)
+                .step_by(0.01)
+                .text("Maze braid")
+            );
+            ui.add(
+                Slider::new(&mut maze.regenerate_every, 0..=100)
+                .text("Maze regenerate every")
+            );
+        }
         ui.add(
             Slider::new(&mut self.timelimit, 1..=1000)
             .text("Timelimit")
@@ -340,6 +690,34 @@ impl RenderableConfig for PointEnvConfig {
             .step_by(0.01)
             .text("Bounce factor")
         );
+        ui.add(
+            Slider::new(&mut self.bounce_noise, 0.0..=0.99)
+            .step_by(0.01)
+            .text("Bounce noise")
+        );
+        ui.add(
+            Slider::new(&mut self.action_noise, 0.0..=1.0)
+            .step_by(0.01)
+            .text("Action noise")
+        );
+        ui.add(
+            Slider::new(&mut self.action_dropout, 0.0..=1.0)
+            .step_by(0.01)
+            .text("Action dropout")
+        );
+        ui.add(
+            Slider::new(&mut self.image_resolution, 0..=64)
+            .text("Image resolution")
+        );
+        ui.add(
+            Slider::new(&mut self.lidar_rays, 0..=64)
+            .text("Lidar rays")
+        );
+        ui.add(
+            Slider::new(&mut self.lidar_range, 0.1..=20.0)
+            .step_by(0.1)
+            .text("Lidar range")
+        );
         ui.add(
             Slider::new(&mut self.seed, 0..=1000)
             .text("Seed")
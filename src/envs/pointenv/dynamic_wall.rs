@@ -0,0 +1,99 @@
+use {
+    super::line::PointLine,
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    anyhow::Result,
+};
+
+/// When a [PointDynamicWall] is closed, i.e. blocks the agent.
+///
+/// # Variants
+/// * `Door` - A door that is open for `open` steps and then closed for `closed`
+///   steps, repeating within every episode. `offset` shifts the cycle by a number of steps.
+/// * `Toggle` - A door that switches between open and closed every `every`
+///   episodes, starting open if `open` is set (an `every` of 0 disables toggling).
+/// * `AppearAfter` - A wall that is absent for the given number of episodes and
+///   closed from then on.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum PointWallSchedule {
+    Door {
+        open: usize,
+        closed: usize,
+        offset: usize,
+    },
+    Toggle {
+        every: usize,
+        open: bool,
+    },
+    AppearAfter(usize),
+}
+impl PointWallSchedule {
+    /// Whether the wall is closed at the `timestep` within the episode, after
+    /// `episodes` completed episodes.
+    pub fn is_closed(
+        &self,
+        timestep: usize,
+        episodes: usize,
+    ) -> bool {
+        match self {
+            Self::Door { open, closed, offset } => (timestep + offset) % (open + closed) >= *open,
+            Self::Toggle { every, open } => {
+                let toggled = *every > 0 && (episodes / every) % 2 == 1;
+                *open == toggled
+            },
+            Self::AppearAfter(n) => episodes >= *n,
+        }
+    }
+}
+
+/// A wall of the [`PointEnv`](super::point_env::PointEnv) environment that
+/// opens and closes according to a [PointWallSchedule].
+///
+/// While closed, the wall behaves exactly like any other wall and is part of
+/// the [`PointObs`](super::observation::PointObs).
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct PointDynamicWall {
+    pub wall: ((f64, f64), (f64, f64)),
+    pub schedule: PointWallSchedule,
+}
+impl PointDynamicWall {
+    pub fn new(
+        wall: ((f64, f64), (f64, f64)),
+        schedule: PointWallSchedule,
+    ) -> Self {
+        Self {
+            wall,
+            schedule,
+        }
+    }
+
+    pub fn line(&self) -> PointLine {
+        PointLine::from(self.wall)
+    }
+
+    pub fn is_closed(
+        &self,
+        timestep: usize,
+        episodes: usize,
+    ) -> bool {
+        self.schedule.is_closed(timestep, episodes)
+    }
+
+    pub fn check(&self) -> Result<()> {
+        let ((x1, y1), (x2, y2)) = self.wall;
+        if ![x1, y1, x2, y2].iter().all(|v| v.is_finite()) {
+            return Err(anyhow::anyhow!("Dynamic walls must have finite coordinates"));
+        }
+        if x1 == x2 && y1 == y2 {
+            return Err(anyhow::anyhow!("Dynamic walls must not be degenerate (both endpoints are equal)"));
+        }
+        if let PointWallSchedule::Door { open, closed, .. } = self.schedule {
+            if open + closed == 0 {
+                return Err(anyhow::anyhow!("Doors must be open or closed for at least one step"));
+            }
+        }
+        Ok(())
+    }
+}
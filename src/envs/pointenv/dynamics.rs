@@ -0,0 +1,127 @@
+use {
+    super::{
+        action::PointAction,
+        state::PointState,
+    },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    rand::{
+        Rng,
+        RngCore,
+    },
+    rand_distr::{
+        Distribution,
+        Normal,
+    },
+    anyhow::Result,
+};
+
+/// Add zero-mean Gaussian noise with standard deviation `std` to both
+/// components of the [PointAction].
+pub fn gaussian_perturbation(
+    action: PointAction,
+    std: f64,
+    rng: &mut dyn RngCore,
+) -> PointAction {
+    if std <= 0.0 {
+        return action;
+    }
+    let normal = Normal::new(0.0, std).expect("Standard deviation must be finite");
+    PointAction::from((
+        action.dx() + normal.sample(rng),
+        action.dy() + normal.sample(rng),
+    ))
+}
+
+/// Sample a bounce factor uniformly within `bounce_noise` (as a fraction) of
+/// the given `bounce_factor`.
+pub fn sample_bounce_factor(
+    bounce_factor: f64,
+    bounce_noise: f64,
+    rng: &mut dyn RngCore,
+) -> f64 {
+    if bounce_noise <= 0.0 {
+        return bounce_factor;
+    }
+    bounce_factor * (1.0 + rng.gen_range(-bounce_noise..=bounce_noise))
+}
+
+/// A rectangular region of the [`PointEnv`](super::point_env::PointEnv) that
+/// perturbs the motion of the agent while it starts a step inside of it.
+///
+/// # Variants
+/// * `Wind` - Adds a constant `force` `(dx, dy)` to every action.
+/// * `Slippery` - Adds Gaussian noise with standard deviation `std` to every action.
+///
+/// Both are given by the corners `min` and `max` of the region.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum PointRegion {
+    Wind {
+        min: (f64, f64),
+        max: (f64, f64),
+        force: (f64, f64),
+    },
+    Slippery {
+        min: (f64, f64),
+        max: (f64, f64),
+        std: f64,
+    },
+}
+impl PointRegion {
+    /// The `(min, max)` corners of the region.
+    pub fn bounds(&self) -> ((f64, f64), (f64, f64)) {
+        match self {
+            Self::Wind { min, max, .. } | Self::Slippery { min, max, .. } => (*min, *max),
+        }
+    }
+
+    /// Check whether the region contains the [PointState].
+    pub fn contains(
+        &self,
+        state: &PointState,
+    ) -> bool {
+        let (min, max) = self.bounds();
+        (min.0..=max.0).contains(&state.x()) && (min.1..=max.1).contains(&state.y())
+    }
+
+    /// Perturb the action taken from `state`, if the state is within the region.
+    pub fn perturb(
+        &self,
+        state: &PointState,
+        action: PointAction,
+        rng: &mut dyn RngCore,
+    ) -> PointAction {
+        if !self.contains(state) {
+            return action;
+        }
+        match self {
+            Self::Wind { force, .. } => PointAction::from((
+                action.dx() + force.0,
+                action.dy() + force.1,
+            )),
+            Self::Slippery { std, .. } => gaussian_perturbation(action, *std, rng),
+        }
+    }
+
+    pub fn check(&self) -> Result<()> {
+        let (min, max) = self.bounds();
+        if !(min.0 < max.0 && min.1 < max.1) {
+            return Err(anyhow::anyhow!("Region min corner must be smaller than its max corner"));
+        }
+        match self {
+            Self::Wind { force, .. } => {
+                if !(force.0.is_finite() && force.1.is_finite()) {
+                    return Err(anyhow::anyhow!("Wind force must be finite"));
+                }
+            },
+            Self::Slippery { std, .. } => {
+                if !(*std >= 0.0 && std.is_finite()) {
+                    return Err(anyhow::anyhow!("Slippery std must be a finite non-negative number"));
+                }
+            },
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+#![allow(non_snake_case)]
+use {
+    super::{
+        line::PointLine,
+        obstacle::PointObstacle,
+        state::PointState,
+    },
+    ordered_float::OrderedFloat,
+};
+
+/// The number of channels of a [PointImage]: walls, agent and goal.
+pub const IMAGE_CHANNELS: usize = 3;
+
+/// The distance from `P` to the closest point on the [PointLine].
+fn distance_to_line(
+    P: &PointState,
+    line: &PointLine,
+) -> f64 {
+    let AB = line.B - line.A;
+    let AP = P - line.A;
+    let length_squared = AB.x().powi(2) + AB.y().powi(2);
+    if length_squared < f64::EPSILON {
+        return P.distance_to(&line.A);
+    }
+    let t = ((AP.x() * AB.x() + AP.y() * AB.y()) / length_squared).clamp(0.0, 1.0);
+    P.distance_to(&(line.A + AB * t))
+}
+
+/// A top-down rasterized view of the [`PointEnv`](super::point_env::PointEnv).
+///
+/// The image has [IMAGE_CHANNELS] channels of `resolution` x `resolution`
+/// pixels, row-major with the first row at the top of the environment:
+/// * The walls and obstacles, which are fixed when the image is created.
+/// * The agent and the goal, which are drawn from the [`PointObs`](super::observation::PointObs)
+///   whenever it is converted into a vector, so they always match its states.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PointImage {
+    width: OrderedFloat<f64>,
+    height: OrderedFloat<f64>,
+    resolution: usize,
+    walls: Vec<OrderedFloat<f64>>,
+}
+impl PointImage {
+    /// Rasterize the walls and obstacles of an environment of size `width` x `height`.
+    ///
+    /// A pixel is marked if its center lies within half a pixel of a wall or
+    /// inside of an obstacle.
+    pub fn new(
+        width: f64,
+        height: f64,
+        resolution: usize,
+        walls: &[PointLine],
+        obstacles: &[PointObstacle],
+    ) -> Self {
+        let (pixel_w, pixel_h) = (width / resolution as f64, height / resolution as f64);
+        let thickness = 0.5 * pixel_w.max(pixel_h);
+        let walls = (0..resolution * resolution)
+            .map(|i| {
+                let (row, col) = (i / resolution, i % resolution);
+                let center = PointState::from((
+                    (col as f64 + 0.5) * pixel_w,
+                    height - (row as f64 + 0.5) * pixel_h,
+                ));
+                let occupied = walls.iter().any(|w| distance_to_line(&center, w) <= thickness)
+                    || obstacles.iter().any(|o| o.contains(&center));
+                OrderedFloat(if occupied { 1.0 } else { 0.0 })
+            })
+            .collect();
+        Self {
+            width: OrderedFloat(width),
+            height: OrderedFloat(height),
+            resolution,
+            walls,
+        }
+    }
+
+    /// Rebuild a [PointImage] of an environment of size `width` x `height`
+    /// from its pixels, see [`PointImage::to_values`].
+    ///
+    /// Panics if the number of pixels does not match [IMAGE_CHANNELS] square images.
+    pub fn from_values(
+        width: f64,
+        height: f64,
+        values: &[f64],
+    ) -> Self {
+        let n_pixels = values.len();
+        let resolution = ((n_pixels / IMAGE_CHANNELS) as f64).sqrt().round() as usize;
+        assert!(IMAGE_CHANNELS * resolution * resolution == n_pixels);
+        Self {
+            width: OrderedFloat(width),
+            height: OrderedFloat(height),
+            resolution,
+            walls: values[..resolution * resolution]
+                .iter()
+                .map(|&v| OrderedFloat(v))
+                .collect(),
+        }
+    }
+
+    pub fn resolution(&self) -> usize {
+        self.resolution
+    }
+
+    pub fn width(&self) -> f64 {
+        *self.width
+    }
+
+    pub fn height(&self) -> f64 {
+        *self.height
+    }
+
+    /// Draw a point into a single channel, spreading it bilinearly over the
+    /// four closest pixels to keep sub-pixel information.
+    fn splat(
+        &self,
+        point: &PointState,
+    ) -> Vec<f64> {
+        let resolution = self.resolution;
+        let mut channel = vec![0.0; resolution * resolution];
+        let u = point.x() / (*self.width / resolution as f64) - 0.5;
+        let v = (*self.height - point.y()) / (*self.height / resolution as f64) - 0.5;
+        let (col, row) = (u.floor(), v.floor());
+        let (du, dv) = (u - col, v - row);
+        for (r, c, weight) in [
+            (row, col, (1.0 - dv) * (1.0 - du)),
+            (row, col + 1.0, (1.0 - dv) * du),
+            (row + 1.0, col, dv * (1.0 - du)),
+            (row + 1.0, col + 1.0, dv * du),
+        ] {
+            let r = (r.max(0.0) as usize).min(resolution - 1);
+            let c = (c.max(0.0) as usize).min(resolution - 1);
+            channel[r * resolution + c] += weight;
+        }
+        channel
+    }
+
+    /// The vector form of the image with the agent at `state` and the goal at
+    /// `goal`, i.e. the pixels `[walls..., agent..., goal...]`.
+    pub fn to_values(
+        &self,
+        state: &PointState,
+        goal: &PointState,
+    ) -> Vec<f64> {
+        let mut values: Vec<f64> = self.walls.iter().map(|v| v.into_inner()).collect();
+        values.extend(self.splat(state));
+        values.extend(self.splat(goal));
+        values
+    }
+}
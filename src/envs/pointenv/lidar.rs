@@ -0,0 +1,75 @@
+use {
+    super::{
+        line::PointLine,
+        obstacle::PointObstacle,
+        state::PointState,
+    },
+    ordered_float::OrderedFloat,
+};
+
+/// A range sensor reading of the [`PointEnv`](super::point_env::PointEnv),
+/// i.e. the normalized distances along `rays` rays around the agent.
+///
+/// The rays are evenly spaced counter-clockwise, starting along the positive
+/// x-axis. Every ray stops at the first wall, obstacle or border of the
+/// environment it hits, and its reading is the distance to that point divided
+/// by the `range` of the sensor. Rays that hit nothing within range read `1.0`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PointLidar {
+    readings: Vec<OrderedFloat<f64>>,
+}
+impl PointLidar {
+    /// Cast `rays` rays of length `range` from `state`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast(
+        state: &PointState,
+        rays: usize,
+        range: f64,
+        width: f64,
+        height: f64,
+        walls: &[PointLine],
+        obstacles: &[PointObstacle],
+    ) -> Self {
+        let corners = [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)];
+        let borders: Vec<PointLine> = corners
+            .iter()
+            .zip(corners.iter().cycle().skip(1))
+            .map(|(&a, &b)| PointLine::from((a, b)))
+            .collect();
+
+        let readings = (0..rays)
+            .map(|i| {
+                let angle = i as f64 * 2.0 * std::f64::consts::PI / rays as f64;
+                let ray = PointLine::from((
+                    (state.x(), state.y()),
+                    (state.x() + range * angle.cos(), state.y() + range * angle.sin()),
+                ));
+                let distance = walls
+                    .iter()
+                    .chain(borders.iter())
+                    .filter_map(|w| w.collision_with(&ray))
+                    .chain(obstacles.iter().filter_map(|o| o.collision_with(&ray)))
+                    .map(|hit| state.distance_to(&hit))
+                    .fold(range, f64::min);
+                OrderedFloat((distance / range).clamp(0.0, 1.0))
+            })
+            .collect();
+        Self { readings }
+    }
+
+    /// Rebuild a [PointLidar] from its readings.
+    pub fn from_values(values: &[f64]) -> Self {
+        Self {
+            readings: values.iter().map(|&v| OrderedFloat(v)).collect(),
+        }
+    }
+
+    /// The normalized distances, one per ray.
+    pub fn to_values(&self) -> Vec<f64> {
+        self.readings.iter().map(|v| v.into_inner()).collect()
+    }
+
+    pub fn rays(&self) -> usize {
+        self.readings.len()
+    }
+}
@@ -0,0 +1,324 @@
+use {
+    super::line::PointLine,
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    strum::EnumIter,
+    rand::{
+        rngs::StdRng,
+        seq::SliceRandom,
+        Rng,
+        SeedableRng,
+    },
+    std::fmt::Display,
+    anyhow::Result,
+};
+
+/// The algorithm used to carve a perfect maze, i.e. a maze with exactly one
+/// path between any two cells.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize, EnumIter)]
+pub enum MazeAlgorithm {
+    /// Randomized depth-first search, which produces long winding corridors.
+    Backtracker,
+    /// Randomized Kruskal, which produces many short dead ends.
+    Kruskal,
+}
+impl Display for MazeAlgorithm {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            Self::Backtracker => write!(f, "Backtracker"),
+            Self::Kruskal => write!(f, "Kruskal"),
+        }
+    }
+}
+
+/// The configuration of a procedurally generated maze for the
+/// [`PointEnv`](super::point_env::PointEnv) environment.
+///
+/// # Fields
+/// * `rows` - The number of cells along the height of the environment.
+/// * `cols` - The number of cells along the width of the environment.
+/// * `algorithm` - The [MazeAlgorithm] used to carve the maze.
+/// * `braid` - The fraction of dead ends that are removed by opening an extra
+///   wall. `0.0` gives a perfect maze, `1.0` a fully braided maze without dead ends.
+/// * `regenerate_every` - Generate a new maze every this many resets (0 disables
+///   it, keeping the maze generated from the config seed).
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct MazeConfig {
+    pub rows: usize,
+    pub cols: usize,
+    pub algorithm: MazeAlgorithm,
+    pub braid: f64,
+    pub regenerate_every: usize,
+}
+impl Default for MazeConfig {
+    fn default() -> Self {
+        Self {
+            rows: 4,
+            cols: 4,
+            algorithm: MazeAlgorithm::Backtracker,
+            braid: 0.0,
+            regenerate_every: 0,
+        }
+    }
+}
+impl MazeConfig {
+    /// Creates a new [MazeConfig].
+    pub fn new(
+        rows: usize,
+        cols: usize,
+        algorithm: MazeAlgorithm,
+        braid: f64,
+        regenerate_every: usize,
+    ) -> Self {
+        Self {
+            rows,
+            cols,
+            algorithm,
+            braid,
+            regenerate_every,
+        }
+    }
+
+    pub fn check(&self) -> Result<()> {
+        if self.rows == 0 || self.cols == 0 {
+            return Err(anyhow::anyhow!("Maze must have at least one row and one column"));
+        }
+
+        if !(0.0..=1.0).contains(&self.braid) {
+            return Err(anyhow::anyhow!("Maze braid must be in the range [0.0, 1.0]"));
+        }
+
+        Ok(())
+    }
+
+    /// Generate the inner walls of a maze that fills a `width` x `height` area.
+    ///
+    /// The same seed always generates the same maze. The outer walls are not
+    /// included, as [`PointEnvWalls`](super::config::PointEnvWalls) adds them.
+    pub fn generate(
+        &self,
+        width: f64,
+        height: f64,
+        seed: u64,
+    ) -> Vec<PointLine> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut maze = Maze::new(self.rows, self.cols);
+        match self.algorithm {
+            MazeAlgorithm::Backtracker => maze.carve_backtracker(&mut rng),
+            MazeAlgorithm::Kruskal => maze.carve_kruskal(&mut rng),
+        }
+        maze.braid(self.braid, &mut rng);
+        maze.walls(width, height)
+    }
+}
+
+/// A grid of cells, where `east[i]` / `north[i]` indicate whether the passage
+/// from cell `i` to its east / north neighbor is open. Row 0 is at the bottom.
+struct Maze {
+    rows: usize,
+    cols: usize,
+    east: Vec<bool>,
+    north: Vec<bool>,
+}
+impl Maze {
+    fn new(
+        rows: usize,
+        cols: usize,
+    ) -> Self {
+        Self {
+            rows,
+            cols,
+            east: vec![false; rows * cols],
+            north: vec![false; rows * cols],
+        }
+    }
+
+    fn neighbors(
+        &self,
+        cell: usize,
+    ) -> Vec<usize> {
+        let (row, col) = (cell / self.cols, cell % self.cols);
+        let mut neighbors = Vec::with_capacity(4);
+        if col + 1 < self.cols {
+            neighbors.push(cell + 1);
+        }
+        if col > 0 {
+            neighbors.push(cell - 1);
+        }
+        if row + 1 < self.rows {
+            neighbors.push(cell + self.cols);
+        }
+        if row > 0 {
+            neighbors.push(cell - self.cols);
+        }
+        neighbors
+    }
+
+    fn passage(
+        &mut self,
+        a: usize,
+        b: usize,
+    ) -> &mut bool {
+        let (lo, hi) = (a.min(b), a.max(b));
+        if hi - lo == self.cols {
+            &mut self.north[lo]
+        } else {
+            &mut self.east[lo]
+        }
+    }
+
+    fn is_open(
+        &self,
+        a: usize,
+        b: usize,
+    ) -> bool {
+        let (lo, hi) = (a.min(b), a.max(b));
+        if hi - lo == self.cols {
+            self.north[lo]
+        } else {
+            self.east[lo]
+        }
+    }
+
+    fn n_open(
+        &self,
+        cell: usize,
+    ) -> usize {
+        self.neighbors(cell)
+            .into_iter()
+            .filter(|&n| self.is_open(cell, n))
+            .count()
+    }
+
+    fn carve_backtracker(
+        &mut self,
+        rng: &mut StdRng,
+    ) {
+        let mut visited = vec![false; self.rows * self.cols];
+        let start = rng.gen_range(0..self.rows * self.cols);
+        let mut stack = vec![start];
+        visited[start] = true;
+        while let Some(&cell) = stack.last() {
+            let unvisited = self
+                .neighbors(cell)
+                .into_iter()
+                .filter(|&n| !visited[n])
+                .collect::<Vec<_>>();
+            match unvisited.choose(rng) {
+                Some(&next) => {
+                    *self.passage(cell, next) = true;
+                    visited[next] = true;
+                    stack.push(next);
+                },
+                None => {
+                    stack.pop();
+                },
+            }
+        }
+    }
+
+    fn carve_kruskal(
+        &mut self,
+        rng: &mut StdRng,
+    ) {
+        fn find(
+            parents: &mut [usize],
+            cell: usize,
+        ) -> usize {
+            let mut root = cell;
+            while parents[root] != root {
+                root = parents[root];
+            }
+            let mut cell = cell;
+            while parents[cell] != root {
+                let parent = parents[cell];
+                parents[cell] = root;
+                cell = parent;
+            }
+            root
+        }
+
+        let mut parents = (0..self.rows * self.cols).collect::<Vec<_>>();
+        let mut edges = (0..self.rows * self.cols)
+            .flat_map(|cell| {
+                self.neighbors(cell)
+                    .into_iter()
+                    .filter(move |&n| n > cell)
+                    .map(move |n| (cell, n))
+            })
+            .collect::<Vec<_>>();
+        edges.shuffle(rng);
+        for (a, b) in edges {
+            let (root_a, root_b) = (find(&mut parents, a), find(&mut parents, b));
+            if root_a != root_b {
+                parents[root_a] = root_b;
+                *self.passage(a, b) = true;
+            }
+        }
+    }
+
+    /// Remove a fraction of the dead ends by opening a wall to a neighbor,
+    /// preferring neighbors that are dead ends themselves.
+    fn braid(
+        &mut self,
+        fraction: f64,
+        rng: &mut StdRng,
+    ) {
+        if fraction <= 0.0 {
+            return;
+        }
+        let mut dead_ends = (0..self.rows * self.cols)
+            .filter(|&cell| self.n_open(cell) == 1)
+            .collect::<Vec<_>>();
+        dead_ends.shuffle(rng);
+        for cell in dead_ends {
+            // An earlier opening may already have removed this dead end
+            if self.n_open(cell) != 1 || !rng.gen_bool(fraction) {
+                continue;
+            }
+            let closed = self
+                .neighbors(cell)
+                .into_iter()
+                .filter(|&n| !self.is_open(cell, n))
+                .collect::<Vec<_>>();
+            let preferred = closed
+                .iter()
+                .copied()
+                .filter(|&n| self.n_open(n) == 1)
+                .collect::<Vec<_>>();
+            let candidates = if preferred.is_empty() { &closed } else { &preferred };
+            if let Some(&next) = candidates.choose(rng) {
+                *self.passage(cell, next) = true;
+            }
+        }
+    }
+
+    /// Convert the closed passages into [PointLine]s, scaled to `width` x `height`.
+    fn walls(
+        &self,
+        width: f64,
+        height: f64,
+    ) -> Vec<PointLine> {
+        let cell_w = width / self.cols as f64;
+        let cell_h = height / self.rows as f64;
+        let mut walls = Vec::new();
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let cell = row * self.cols + col;
+                let (x, y) = (col as f64 * cell_w, row as f64 * cell_h);
+                if col + 1 < self.cols && !self.east[cell] {
+                    walls.push(PointLine::from(((x + cell_w, y), (x + cell_w, y + cell_h))));
+                }
+                if row + 1 < self.rows && !self.north[cell] {
+                    walls.push(PointLine::from(((x, y + cell_h), (x + cell_w, y + cell_h))));
+                }
+            }
+        }
+        walls
+    }
+}
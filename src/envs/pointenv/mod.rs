@@ -2,7 +2,13 @@ mod action;
 mod observation;
 mod state;
 
+pub mod dynamic_wall;
+pub mod dynamics;
+pub mod image;
+pub mod lidar;
 pub mod line;
+pub mod maze;
+pub mod obstacle;
 pub mod reward;
 pub mod config;
 pub mod point_env;
@@ -6,6 +6,11 @@ use {
             VectorConvertible,
             GoalAwareObservation,
         },
+        image::{
+            PointImage,
+            IMAGE_CHANNELS,
+        },
+        lidar::PointLidar,
         line::PointLine,
         state::PointState,
     },
@@ -13,18 +18,122 @@ use {
         Device,
         Tensor,
     },
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    std::sync::Mutex,
 };
 
+/// The layouts of the [PointObs] vectors of all environments created so far.
+static LAYOUTS: Mutex<Vec<PointObsLayout>> = Mutex::new(Vec::new());
+
+/// The layout of the vector form of a [PointObs], which is fixed by the config
+/// of its [`PointEnv`](super::point_env::PointEnv).
+///
+/// The vector only holds what the agent observes, so neither the number of
+/// lidar readings nor the size of the environment that is needed to decode an
+/// image are part of it. Instead
+/// every environment registers its layout when it is created, and vectors are
+/// decoded with the layout of their length.
+///
+/// * `lidar_rays` - The number of lidar readings (0 without lidar).
+/// * `image` - The `(width, height, resolution)` of the image, if any.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct PointObsLayout {
+    pub lidar_rays: usize,
+    pub image: Option<(f64, f64, usize)>,
+}
+impl PointObsLayout {
+    /// The length of the vector form.
+    pub fn size(&self) -> usize {
+        let mut size = 2 + 2 + self.lidar_rays;
+        if let Some((_, _, resolution)) = self.image {
+            size += IMAGE_CHANNELS * resolution.pow(2);
+        }
+        size
+    }
+
+    /// Register the layout for decoding vectors of its length.
+    ///
+    /// Fails if a different layout of the same length was registered before,
+    /// since their vectors could not be told apart.
+    pub fn register(self) -> Result<()> {
+        let mut layouts = LAYOUTS.lock().unwrap_or_else(|e| e.into_inner());
+        match layouts.iter().find(|layout| layout.size() == self.size()) {
+            Some(layout) if *layout != self => Err(anyhow!(
+                "The observations of {self:?} cannot be told apart from those of {layout:?}",
+            )),
+            Some(_) => Ok(()),
+            None => {
+                layouts.push(self);
+                Ok(())
+            },
+        }
+    }
+
+    /// The registered layout of vectors of length `size`.
+    fn lookup(size: usize) -> Option<Self> {
+        let layouts = LAYOUTS.lock().unwrap_or_else(|e| e.into_inner());
+        layouts.iter().find(|layout| layout.size() == size).copied()
+    }
+}
+
+/// The view part of a [PointObs], i.e. what the agent perceives of its
+/// surroundings besides its own position and the goal.
+///
+/// It consists of the list of [PointLine]s which represent the obstacles in
+/// the environment and, with range sensors enabled, the [PointLidar] reading
+/// at the current [PointState]. With image observations enabled it also holds
+/// a [PointImage], which adds a top-down rendering of the walls, agent and goal
+/// to the vector form.
+///
+/// The image only stores the walls, the agent and goal are drawn from the
+/// [PointObs] it is part of, so the view can be combined with other goals.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PointView {
+    pub walls: Vec<PointLine>,
+    pub lidar: Option<PointLidar>,
+    pub image: Option<PointImage>,
+}
+
 /// The observation type for the [`PointEnv`](super::point_env::PointEnv) environment
 ///
 /// A [PointObs] is a [GoalAwareObservation] which consists of the current
-/// [PointState], the goal [PointState] and a list of [PointLine]s which
-/// represent the obstacles in the environment.
+/// [PointState], the goal [PointState] and a [PointView] of the walls in the
+/// environment.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PointObs {
     state: PointState,
     goal: PointState,
-    obs: Vec<PointLine>,
+    obs: PointView,
+}
+impl PointObs {
+    /// Attach a [PointLidar] reading to the view (or remove it with `None`).
+    pub fn with_lidar(
+        mut self,
+        lidar: Option<PointLidar>,
+    ) -> Self {
+        self.obs.lidar = lidar;
+        self
+    }
+
+    pub fn lidar(&self) -> Option<&PointLidar> {
+        self.obs.lidar.as_ref()
+    }
+
+    /// Attach a [PointImage] to the view (or remove it with `None`).
+    pub fn with_image(
+        mut self,
+        image: Option<PointImage>,
+    ) -> Self {
+        self.obs.image = image;
+        self
+    }
+
+    pub fn image(&self) -> Option<&PointImage> {
+        self.obs.image.as_ref()
+    }
 }
 
 impl From<(PointState, PointState, &[PointLine])> for PointObs {
@@ -33,14 +142,18 @@ impl From<(PointState, PointState, &[PointLine])> for PointObs {
         Self {
             state: value.0,
             goal: value.1,
-            obs: value.2.to_vec(),
+            obs: PointView {
+                walls: value.2.to_vec(),
+                lidar: None,
+                image: None,
+            },
         }
     }
 }
 
 impl GoalAwareObservation for PointObs {
     type State = PointState;
-    type View = Vec<PointLine>;
+    type View = PointView;
 
     /// The achieved goal is the current [PointState]
     fn achieved_goal(&self) -> &Self::State {
@@ -52,7 +165,8 @@ impl GoalAwareObservation for PointObs {
         &self.goal
     }
 
-    /// The observation is the list of [PointLine]s representing the obstacles
+    /// The observation is the [PointView] of the walls, the lidar reading and
+    /// the image
     fn observation(&self) -> &Self::View {
         &self.obs
     }
@@ -111,6 +225,10 @@ impl VectorConvertible for PointObs {
     /// Because we cannot know the number of walls in the environment at compile
     /// time, we cannot exactly check the length of the vector. The best we can
     /// do is panic unless the length is at least 4 and is divisible by 4.
+    ///
+    /// Longer vectors hold lidar and image observations, see [`VectorConvertible::to_vec`].
+    /// They are decoded with the [PointObsLayout] of the environment, and this
+    /// panics if no environment with a layout of this length was created.
     fn from_vec(value: Vec<f64>) -> Self {
         let state = PointState::from((value[0], value[1]));
         let goal = PointState::from((value[2], value[3]));
@@ -124,7 +242,16 @@ impl VectorConvertible for PointObs {
         //         ))
         //     })
         //     .collect();
-        let obs = Vec::new();
+        let mut obs = PointView::default();
+        if value.len() > 4 {
+            let layout = PointObsLayout::lookup(value.len())
+                .unwrap_or_else(|| panic!("No PointEnv has observations of length {}", value.len()));
+            let rays = layout.lidar_rays;
+            obs.lidar = (rays > 0).then(|| PointLidar::from_values(&value[4..4 + rays]));
+            obs.image = layout
+                .image
+                .map(|(width, height, _)| PointImage::from_values(width, height, &value[4 + rays..]));
+        }
         Self { state, goal, obs }
     }
 
@@ -133,16 +260,28 @@ impl VectorConvertible for PointObs {
     ///
     /// The length of the vector will be `2 + 2 + 4 * n`, where `n` is the
     /// number of walls in the environment.
+    ///
+    /// With a [PointLidar] or a [PointImage], the vector is instead
+    /// `[Sx, Sy, Gx, Gy, D1, ..., DK]` with the `K` lidar readings (none
+    /// without lidar), followed by the walls, agent and goal channels of the
+    /// image if there is one. Its length is thus `4 + K + 3 * resolution^2`.
     fn to_vec(value: Self) -> Vec<f64> {
         // let mut v = vec![value.state.x(), value.state.y(), value.goal.x(), value.goal.y()];
         // v.extend(value.obs.iter().flat_map(|l| vec![l.A.x(), l.A.y(), l.B.x(), l.B.y()]));
         // v
-        vec![
+        let mut v = vec![
             value.state.x(),
             value.state.y(),
             value.goal.x(),
             value.goal.y(),
-        ]
+        ];
+        if let Some(lidar) = &value.obs.lidar {
+            v.extend(lidar.to_values());
+        }
+        if let Some(image) = &value.obs.image {
+            v.extend(image.to_values(&value.state, &value.goal));
+        }
+        v
     }
 }
 
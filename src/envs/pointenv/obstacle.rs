@@ -0,0 +1,180 @@
+use {
+    super::{
+        line::PointLine,
+        state::PointState,
+    },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    anyhow::Result,
+};
+
+fn dot(
+    a: &PointState,
+    b: &PointState,
+) -> f64 {
+    a.x() * b.x() + a.y() * b.y()
+}
+
+/// A solid obstacle for the [`PointEnv`](super::point_env::PointEnv) environment.
+///
+/// Unlike [PointLine] walls, obstacles are filled: the agent can neither move
+/// through nor spawn inside of them. An agent that runs into an obstacle is
+/// reflected off its surface, see [PointObstacle::reflect].
+///
+/// # Variants
+/// * `Polygon` - A simple polygon given by its vertices `(x, y)` in order. The
+///   polygon is closed automatically and may be concave.
+/// * `Circle` - A circle given by its `center` and `radius`.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum PointObstacle {
+    Polygon(Vec<(f64, f64)>),
+    Circle {
+        center: (f64, f64),
+        radius: f64,
+    },
+}
+impl PointObstacle {
+    /// The edges of a polygon as [PointLine]s, including the closing edge.
+    fn edges(vertices: &[(f64, f64)]) -> impl Iterator<Item = PointLine> + '_ {
+        vertices
+            .iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .map(|(&a, &b)| PointLine::from((a, b)))
+    }
+
+    /// Check whether the obstacle contains the [PointState].
+    pub fn contains(
+        &self,
+        state: &PointState,
+    ) -> bool {
+        match self {
+            Self::Polygon(vertices) => {
+                // Even-odd rule: count the edges crossed by a ray towards +x
+                let (px, py) = (state.x(), state.y());
+                let mut inside = false;
+                for (&(x1, y1), &(x2, y2)) in vertices.iter().zip(vertices.iter().cycle().skip(1)) {
+                    if (y1 > py) != (y2 > py) && px < x1 + (py - y1) * (x2 - x1) / (y2 - y1) {
+                        inside = !inside;
+                    }
+                }
+                inside
+            },
+            Self::Circle { center, radius } => {
+                state.in_radius_of(&PointState::from(*center), *radius)
+            },
+        }
+    }
+
+    /// Returns the first point where the line enters the obstacle, if any.
+    ///
+    /// The line is assumed to go from `line.A` to `line.B`, so the first point is
+    /// the collision closest to `line.A`.
+    pub fn collision_with(
+        &self,
+        line: &PointLine,
+    ) -> Option<PointState> {
+        self.hit(line).map(|(collision, _)| collision)
+    }
+
+    /// Like [PointObstacle::collision_with], but also returns the unit normal
+    /// of the surface at the collision, pointing out of the obstacle.
+    pub fn hit(
+        &self,
+        line: &PointLine,
+    ) -> Option<(PointState, PointState)> {
+        match self {
+            Self::Polygon(vertices) => Self::edges(vertices)
+                .filter_map(|edge| line.collision_with(&edge).map(|point| (point, edge)))
+                .min_by(|(a, _), (b, _)| line.A.distance_to(a).total_cmp(&line.A.distance_to(b)))
+                .map(|(point, edge)| {
+                    let along = edge.B - edge.A;
+                    let normal = PointState::from((-along.y(), along.x())) / along.magnitude();
+                    // the line comes from outside, so the outer side faces line.A
+                    if dot(&normal, &(line.A - point)) < 0.0 {
+                        (point, normal * -1.0)
+                    } else {
+                        (point, normal)
+                    }
+                }),
+            Self::Circle { center, radius } => {
+                // Solve |A + t * (B - A) - C|^2 = r^2 for the smallest t in [0, 1]
+                let center = PointState::from(*center);
+                let direction = line.B - line.A;
+                let offset = line.A - center;
+                let a = direction.x().powi(2) + direction.y().powi(2);
+                let b = 2.0 * (offset.x() * direction.x() + offset.y() * direction.y());
+                let c = offset.x().powi(2) + offset.y().powi(2) - radius.powi(2);
+                let discriminant = b.powi(2) - 4.0 * a * c;
+                // A line that starts inside or on the circle does not enter it
+                if c <= 0.0 || a < f64::EPSILON || discriminant < 0.0 {
+                    return None;
+                }
+                let t = (-b - discriminant.sqrt()) / (2.0 * a);
+                if (0.0..=1.0).contains(&t) {
+                    let point = line.A + direction * t;
+                    Some((point, (point - center) / *radius))
+                } else {
+                    None
+                }
+            },
+        }
+    }
+
+    /// Reflect the movement from `position` to `target` about the surface with
+    /// the unit `normal` that it hits at `collision`.
+    ///
+    /// The angle of incidence equals the angle of reflection, and like
+    /// [`PointLine::bounce_from_obstacle`] the agent ends up `bounce_factor`
+    /// away from the collision.
+    pub fn reflect(
+        position: PointState,
+        target: PointState,
+        collision: PointState,
+        normal: PointState,
+        bounce_factor: f64,
+    ) -> PointState {
+        let incoming = target - position;
+        let reflected = incoming - normal * (2.0 * dot(&incoming, &normal));
+        collision + reflected / reflected.magnitude() * bounce_factor
+    }
+
+    /// The outline of the obstacle, with circles approximated by `resolution` points.
+    pub fn outline(
+        &self,
+        resolution: usize,
+    ) -> Vec<[f64; 2]> {
+        match self {
+            Self::Polygon(vertices) => vertices.iter().map(|&(x, y)| [x, y]).collect(),
+            Self::Circle { center, radius } => (0..resolution)
+                .map(|i| {
+                    let angle = i as f64 * 2.0 * std::f64::consts::PI / resolution as f64;
+                    [center.0 + radius * angle.cos(), center.1 + radius * angle.sin()]
+                })
+                .collect(),
+        }
+    }
+
+    pub fn check(&self) -> Result<()> {
+        match self {
+            Self::Polygon(vertices) => {
+                if vertices.len() < 3 {
+                    return Err(anyhow::anyhow!("Polygon obstacles need at least 3 vertices"));
+                }
+                if !vertices.iter().all(|(x, y)| x.is_finite() && y.is_finite()) {
+                    return Err(anyhow::anyhow!("Polygon obstacles must have finite vertices"));
+                }
+            },
+            Self::Circle { center, radius } => {
+                if !(center.0.is_finite() && center.1.is_finite()) {
+                    return Err(anyhow::anyhow!("Circle obstacles must have a finite center"));
+                }
+                if !(*radius > 0.0 && radius.is_finite()) {
+                    return Err(anyhow::anyhow!("Circle obstacles must have a positive radius"));
+                }
+            },
+        }
+        Ok(())
+    }
+}
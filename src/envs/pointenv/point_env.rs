@@ -3,13 +3,29 @@ use {
         super::{
             Environment,
             RenderableEnvironment,
+            RecordableEnvironment,
             Sampleable,
             Step,
         },
         action::PointAction,
-        config::PointEnvConfig,
+        config::{
+            PointEnvConfig,
+            PointEnvWalls,
+        },
+        dynamic_wall::PointDynamicWall,
+        image::PointImage,
+        dynamics::{
+            gaussian_perturbation,
+            sample_bounce_factor,
+            PointRegion,
+        },
+        lidar::PointLidar,
         line::PointLine,
-        observation::PointObs,
+        obstacle::PointObstacle,
+        observation::{
+            PointObs,
+            PointObsLayout,
+        },
         reward::PointReward,
         state::PointState,
     },
@@ -25,6 +41,7 @@ use {
     ordered_float::OrderedFloat,
     rand::{
         rngs::StdRng,
+        Rng,
         RngCore,
         SeedableRng,
     },
@@ -36,7 +53,7 @@ use {
 ///
 /// A pair is valid if the goal is not reachable from the start within a single
 /// step, and there are no wall collisions i.e. neither start nor goal is
-/// contained within a wall / [PointLine].
+/// contained within a wall / [PointLine] or a [PointObstacle].
 ///
 /// If `spawn_radius_max` is provided, we also make sure that the distance between
 /// start and goal is less than `spawn_radius_max`.
@@ -49,6 +66,7 @@ fn generate_start_goal(
     spawn_radius_min: Option<f64>,
     spawn_centers: Option<(PointState, PointState)>,
     walls: &[PointLine],
+    obstacles: &[PointObstacle],
     rng: &mut dyn RngCore,
 ) -> (PointState, PointState) {
 
@@ -70,7 +88,9 @@ fn generate_start_goal(
                     continue;
                 }
             }
-            if !walls.iter().any(|w| w.contains(&state)) {
+            if !walls.iter().any(|w| w.contains(&state))
+                && !obstacles.iter().any(|o| o.contains(&state))
+            {
                 break state;
             }
         }
@@ -118,8 +138,14 @@ fn generate_start_goal(
             let wall_contains_state = walls
                 .iter()
                 .any(|w| w.contains(&start) || w.contains(&goal));
+            let obstacle_contains_state = obstacles
+                .iter()
+                .any(|o| o.contains(&start) || o.contains(&goal));
 
-            if !wall_contains_state && !reachable(&start, &goal, step_radius, walls) {
+            if !wall_contains_state
+                && !obstacle_contains_state
+                && !reachable(&start, &goal, step_radius, walls, obstacles)
+            {
                 break (start, goal)
             }
         }
@@ -127,22 +153,43 @@ fn generate_start_goal(
 }
 
 /// The goal is reachable from state if they are within `step_radius` of each
-/// other and none of the `walls` would block a straight line between them.
+/// other and none of the `walls` or `obstacles` would block a straight line
+/// between them.
 pub fn reachable(
     state: &PointState,
     goal: &PointState,
     step_radius: f64,
     walls: &[PointLine],
+    obstacles: &[PointObstacle],
 ) -> bool {
+    let line = PointLine::from((*state, *goal));
     return state.in_radius_of(goal, step_radius)
-        && !walls.iter().any(|w| {
-            w.collision_with(&PointLine::from((*state, *goal)))
-                .is_some()
-        });
+        && !walls.iter().any(|w| w.collision_with(&line).is_some())
+        && !obstacles.iter().any(|o| o.collision_with(&line).is_some());
+}
+
+/// Combine the static `layout` walls with the `dynamic_walls` that are closed
+/// at the `timestep` within the episode, after `episodes` completed episodes.
+fn active_walls(
+    layout: &[PointLine],
+    dynamic_walls: &[PointDynamicWall],
+    timestep: usize,
+    episodes: usize,
+) -> Vec<PointLine> {
+    layout
+        .iter()
+        .copied()
+        .chain(
+            dynamic_walls
+                .iter()
+                .filter(|w| w.is_closed(timestep, episodes))
+                .map(|w| w.line()),
+        )
+        .collect()
 }
 
 /// Compute the `next_state` after taking `action` in `state`, considering any
-/// possible collisions with `walls`.
+/// possible collisions with `walls` and `obstacles`.
 fn compute_next_state(
     width: f64,
     height: f64,
@@ -150,38 +197,42 @@ fn compute_next_state(
     action: &PointAction,
     bounce_factor: f64,
     walls: &[PointLine],
+    obstacles: &[PointObstacle],
 ) -> PointState {
-    // make a line from A to B, collect any collisions
+    // make a line from A to B, find the closest collision, with the normal
+    // of the surface for obstacles
     let movement_line = PointLine::from((state, state + action));
-    let collisions: Vec<PointState> = walls
+    let closest = walls
         .iter()
-        .filter_map(|w| w.collision_with(&movement_line))
-        .collect();
+        .filter_map(|w| w.collision_with(&movement_line).map(|c| (c, None)))
+        .chain(obstacles.iter().filter_map(|o| o.hit(&movement_line).map(|(c, n)| (c, Some(n)))))
+        .min_by(|(first, _), (second, _)| first.distance_to(&state).total_cmp(&second.distance_to(&state)));
 
     // decide the next state
-    let next_state = match collisions.len() {
+    let next_state = match closest {
         // no collisions? easy
-        0 => state + action,
+        None => state + action,
 
-        // one collision? bounce back from collision
-        1 => PointLine::bounce_from_obstacle(state, collisions[0], OrderedFloat(bounce_factor)),
+        // a wall? bounce back from collision
+        Some((collision, None)) => PointLine::bounce_from_obstacle(state, collision, OrderedFloat(bounce_factor)),
 
-        // multiple collisions? bounce back from closest collision
-        _ => {
-            let argclosest = collisions
+        // an obstacle? reflect off its surface, unless that runs into
+        // something else, then bounce back like from a wall
+        Some((collision, Some(normal))) => {
+            let reflected = PointObstacle::reflect(state, state + action, collision, normal, bounce_factor);
+            let reflected_line = PointLine::from((collision, reflected));
+            let blocked = walls
                 .iter()
-                .enumerate()
-                .map(|(idx, point)| (idx, point.distance_to(&state)))
-                .min_by(|(_, first), (_, second)| first.total_cmp(second))
-                .expect("Collisions cannot be an empty vector, we covered that case")
-                .0;
-
-            PointLine::bounce_from_obstacle(
-                state,
-                collisions[argclosest],
-                OrderedFloat(bounce_factor),
-            )
-        }
+                .filter_map(|w| w.collision_with(&reflected_line))
+                .chain(obstacles.iter().filter_map(|o| o.collision_with(&reflected_line)))
+                .any(|c| c.distance_to(&collision) > f64::EPSILON.sqrt())
+                || obstacles.iter().any(|o| o.contains(&reflected));
+            if blocked {
+                PointLine::bounce_from_obstacle(state, collision, OrderedFloat(bounce_factor))
+            } else {
+                reflected
+            }
+        },
     };
 
     info!(
@@ -208,15 +259,19 @@ fn compute_next_state(
 /// step size. The environment is bounded by walls, and more walls can be added
 /// to the environment to make it more difficult.
 ///
-/// If the agent collides with a wall, it will bounce off the wall by a factor
-/// of the travelled distance. The angle of incidence here is equal to the angle
-/// of reflection.
+/// If the agent collides with a wall, it will bounce back along its path by a
+/// factor of the travelled distance. If it collides with an obstacle, it is
+/// reflected off its surface instead, so the angle of incidence is equal to the
+/// angle of reflection.
 #[derive(Clone)]
 pub struct PointEnv {
     config: PointEnvConfig,
     width: f64,
     height: f64,
     walls: Vec<PointLine>,
+    layout_walls: Vec<PointLine>,
+    dynamic_walls: Vec<PointDynamicWall>,
+    obstacles: Vec<PointObstacle>,
 
     state: PointState,
     start: PointState,
@@ -233,6 +288,14 @@ pub struct PointEnv {
     spawn_radius_min: Option<f64>,
     spawn_centers: Option<(PointState, PointState)>,
     bounce_factor: f64,
+    bounce_noise: f64,
+    action_noise: f64,
+    action_dropout: f64,
+    regions: Vec<PointRegion>,
+    image_resolution: usize,
+    image: Option<PointImage>,
+    lidar_rays: usize,
+    lidar_range: f64,
     reward: PointReward,
 
     rng: StdRng,
@@ -240,12 +303,21 @@ pub struct PointEnv {
 impl PointEnv {
     fn new(config: PointEnvConfig) -> Result<Box<Self>> {
         config.check()?;
+        PointEnv::layout(&config).register()?;
 
         // add walls
-        let walls = config.walls.to_walls(config.width, config.height);
+        let layout_walls = config.walls.to_walls(config.width, config.height, config.seed);
+        let walls = active_walls(&layout_walls, &config.dynamic_walls, 0, 0);
 
         // compute random start and goal
         let mut rng = StdRng::seed_from_u64(config.seed);
+        let image = (config.image_resolution > 0).then(|| PointImage::new(
+            config.width,
+            config.height,
+            config.image_resolution,
+            &walls,
+            &config.obstacles,
+        ));
         let (start, goal) = generate_start_goal(
             config.width,
             config.height,
@@ -254,6 +326,7 @@ impl PointEnv {
             config.spawn_radius_min,
             config.spawn_centers.map(|(start, goal)| (start.into(), goal.into())),
             &walls,
+            &config.obstacles,
             &mut rng,
         );
 
@@ -262,6 +335,9 @@ impl PointEnv {
             width: config.width,
             height: config.height,
             walls,
+            layout_walls,
+            dynamic_walls: config.dynamic_walls.clone(),
+            obstacles: config.obstacles.clone(),
 
             state: start,
             start,
@@ -278,6 +354,14 @@ impl PointEnv {
             spawn_radius_min: config.spawn_radius_min,
             spawn_centers: config.spawn_centers.map(|(start, goal)| (start.into(), goal.into())),
             bounce_factor: config.bounce_factor,
+            bounce_noise: config.bounce_noise,
+            action_noise: config.action_noise,
+            action_dropout: config.action_dropout,
+            regions: config.regions.clone(),
+            image_resolution: config.image_resolution,
+            image,
+            lidar_rays: config.lidar_rays,
+            lidar_range: config.lidar_range,
             reward: config.reward,
 
             rng,
@@ -296,6 +380,99 @@ impl PointEnv {
         &self.walls
     }
 
+    pub fn dynamic_walls(&self) -> &Vec<PointDynamicWall> {
+        &self.dynamic_walls
+    }
+
+    /// The number of completed episodes, which drives episode based wall schedules.
+    fn completed_episodes(&self) -> usize {
+        self.reset_count.saturating_sub(1)
+    }
+
+    /// Recompute the currently closed walls from the layout and dynamic walls.
+    fn update_walls(&mut self) {
+        if self.dynamic_walls.is_empty() {
+            return;
+        }
+        let walls = active_walls(
+            &self.layout_walls,
+            &self.dynamic_walls,
+            self.timestep,
+            self.completed_episodes(),
+        );
+        if walls != self.walls {
+            self.walls = walls;
+            self.update_image();
+        }
+    }
+
+    /// The layout of the observation vectors of an environment with this config.
+    fn layout(config: &PointEnvConfig) -> PointObsLayout {
+        PointObsLayout {
+            lidar_rays: config.lidar_rays,
+            image: (config.image_resolution > 0)
+                .then_some((config.width, config.height, config.image_resolution)),
+        }
+    }
+
+    /// Re-rasterize the walls and obstacles for image observations.
+    fn update_image(&mut self) {
+        if self.image_resolution > 0 {
+            self.image = Some(PointImage::new(
+                self.width,
+                self.height,
+                self.image_resolution,
+                &self.walls,
+                &self.obstacles,
+            ));
+        }
+    }
+
+    /// The observation of the agent at `state`, including the lidar reading
+    /// and the image if enabled.
+    fn observation(
+        &self,
+        state: PointState,
+    ) -> PointObs {
+        let lidar = (self.lidar_rays > 0).then(|| PointLidar::cast(
+            &state,
+            self.lidar_rays,
+            self.lidar_range,
+            self.width,
+            self.height,
+            &self.walls,
+            &self.obstacles,
+        ));
+        PointObs::from((state, self.goal, self.walls.as_ref()))
+            .with_lidar(lidar)
+            .with_image(self.image.clone())
+    }
+
+    pub fn obstacles(&self) -> &Vec<PointObstacle> {
+        &self.obstacles
+    }
+
+    pub fn regions(&self) -> &Vec<PointRegion> {
+        &self.regions
+    }
+
+    /// Apply the stochastic dynamics to the intended action, i.e. action
+    /// dropout, Gaussian action noise and the perturbations of any regions
+    /// containing the current state. All randomness comes from the env RNG.
+    fn perturb_action(
+        &mut self,
+        action: PointAction,
+    ) -> PointAction {
+        if self.action_dropout > 0.0 && self.rng.gen_bool(self.action_dropout) {
+            return PointAction::from((0.0, 0.0));
+        }
+        let mut action = gaussian_perturbation(action, self.action_noise, &mut self.rng);
+        for region in self.regions.iter() {
+            action = region.perturb(&self.state, action, &mut self.rng);
+        }
+        action.restrict(self.step_radius)
+    }
+
     pub fn state(&self) -> &PointState {
         &self.state
     }
@@ -318,6 +495,14 @@ impl Environment for PointEnv {
     type Action = PointAction;
     type Observation = PointObs;
 
+    /// The seed sets the layout of generated walls and the start and goal.
+    fn seed_config(
+        config: &mut Self::Config,
+        seed: u64,
+    ) {
+        config.seed = seed;
+    }
+
     /// Create a new [PointEnv] with the given [PointEnvConfig].
     ///
     /// # Panics
@@ -337,6 +522,10 @@ impl Environment for PointEnv {
     }
 
     /// Reset the environment to a new episode, with a new random start and goal.
+    ///
+    /// If the walls are a [`PointEnvWalls::Maze`] with `regenerate_every` set,
+    /// a new maze is generated from the seed every `regenerate_every` resets.
+    /// Dynamic walls are updated to their state at the start of the new episode.
     fn reset(
         &mut self,
         seed: u64,
@@ -344,6 +533,16 @@ impl Environment for PointEnv {
         self.timestep = 0;
         self.reset_count += 1;
 
+        // regenerate procedural walls if requested
+        if let PointEnvWalls::Maze(maze) = &self.config.walls {
+            if maze.regenerate_every > 0 && self.reset_count % maze.regenerate_every == 0 {
+                self.layout_walls = self.config.walls.to_walls(self.width, self.height, seed);
+                self.walls = self.layout_walls.clone();
+                self.update_image();
+            }
+        }
+        self.update_walls();
+
         // compute random start and goal
         self.rng = StdRng::seed_from_u64(seed);
         (self.start, self.goal) = generate_start_goal(
@@ -354,13 +553,14 @@ impl Environment for PointEnv {
             self.spawn_radius_min,
             self.spawn_centers,
             &self.walls,
+            &self.obstacles,
             &mut self.rng,
         );
         self.state = self.start;
 
         self.history = vec![self.start];
 
-        Ok(PointObs::from((self.start, self.goal, self.walls.as_ref())))
+        Ok(self.observation(self.start))
     }
 
     /// Take a step in the environment, returning the new observation and reward.
@@ -371,6 +571,9 @@ impl Environment for PointEnv {
     /// - `reward`: the reward for taking the action
     /// - `terminated`: whether the episode is terminated
     /// - `truncated`: whether the episode is truncated
+    ///
+    /// With stochastic dynamics configured, the performed action may differ
+    /// from the given one, but the returned `action` is always the intended one.
     fn step(
         &mut self,
         action: Self::Action,
@@ -378,23 +581,29 @@ impl Environment for PointEnv {
         let action = action.restrict(self.step_radius);
         self.timestep += 1;
 
+        let performed_action = self.perturb_action(action);
+        let bounce_factor = sample_bounce_factor(self.bounce_factor, self.bounce_noise, &mut self.rng);
         self.state = compute_next_state(
             self.width,
             self.height,
             self.state,
-            &action,
-            self.bounce_factor,
+            &performed_action,
+            bounce_factor,
             &self.walls,
+            &self.obstacles,
         );
 
         self.history.push(self.state);
 
         let reward = self
             .reward
-            .compute(&self.state, &self.goal, self.term_radius, &self.walls);
-        let terminated = reachable(&self.state, &self.goal, self.term_radius, &self.walls);
+            .compute(&self.state, &self.goal, self.term_radius, &self.walls, &self.obstacles);
+        let terminated = reachable(&self.state, &self.goal, self.term_radius, &self.walls, &self.obstacles);
         let truncated = !terminated && (self.timestep >= self.timelimit);
 
+        // doors may open or close for the next step
+        self.update_walls();
+
         info!(
             concat!(
                 "\nPointEnv Step:",
@@ -412,7 +621,7 @@ impl Environment for PointEnv {
         );
 
         Ok(Step {
-            observation: PointObs::from((self.state, self.goal, self.walls.as_ref())),
+            observation: self.observation(self.state),
             action,
             reward,
             terminated,
@@ -441,8 +650,12 @@ impl Environment for PointEnv {
     /// - `2`: the x and y coordinates of the agent
     /// - `2`: the x and y coordinates of the goal
     /// - `4 * n`: the x and y coordinates of the start and end points of each wall
+    ///
+    /// With lidar or image observations, the walls are replaced by the `K`
+    /// normalized [PointLidar] distances, followed by a [PointImage] of
+    /// `3 * resolution^2` pixels if images are enabled.
     fn observation_space(&self) -> Vec<usize> {
-        vec![2 + 2] // + 4 * self.walls.len()]
+        vec![PointEnv::layout(&self.config).size()] // + 4 * self.walls.len()
     }
 
     /// The observation domain of [PointEnv] is `[0.0..=width, 0.0..=height; 4 + 4 * n]`,
@@ -452,7 +665,7 @@ impl Environment for PointEnv {
 
     /// Return the current observation of the environment.
     fn current_observation(&self) -> Self::Observation {
-        PointObs::from((self.state, self.goal, self.walls.as_ref()))
+        self.observation(self.state)
     }
 
     /// Return the value range of the reward function, with a 40% padding on the upper bound.
@@ -491,6 +704,37 @@ impl RenderableEnvironment for PointEnv {
                     .color(Color32::WHITE),
             )
         }
+        // Plot dynamic walls, open ones dimmed and closed ones highlighted
+        for dynamic_wall in self.dynamic_walls().iter() {
+            let wall = dynamic_wall.line();
+            let closed = self.walls().contains(&wall);
+            plot_ui.line(
+                Line::new(vec![[wall.A.x(), wall.A.y()], [wall.B.x(), wall.B.y()]])
+                    .width(if closed { 3.0 } else { 1.0 })
+                    .color(if closed { Color32::from_rgb(255, 165, 0) } else { Color32::DARK_GRAY }),
+            )
+        }
+        // Plot regions
+        for region in self.regions().iter() {
+            let ((x1, y1), (x2, y2)) = region.bounds();
+            let color = match region {
+                PointRegion::Wind { .. } => Color32::from_rgba_unmultiplied(100, 150, 255, 40),
+                PointRegion::Slippery { .. } => Color32::from_rgba_unmultiplied(100, 255, 255, 40),
+            };
+            plot_ui.polygon(
+                Polygon::new(vec![[x1, y1], [x2, y1], [x2, y2], [x1, y2]])
+                    .fill_color(color)
+                    .stroke((1.0, color)),
+            );
+        }
+        // Plot obstacles
+        for obstacle in self.obstacles().iter() {
+            plot_ui.polygon(
+                Polygon::new(obstacle.outline(32))
+                    .fill_color(Color32::from_white_alpha(64))
+                    .stroke((2.0, Color32::WHITE)),
+            );
+        }
         // Plot start and goal
         let start = self.start();
         plot_ui.points(
@@ -511,6 +755,21 @@ impl RenderableEnvironment for PointEnv {
                 .map(|p| [p.x(), p.y()])
                 .collect::<Vec<_>>(),
         ));
+        // Plot the lidar rays from the current state up to where they hit
+        if let Some(lidar) = self.current_observation().lidar() {
+            let state = self.state;
+            for (i, reading) in lidar.to_values().into_iter().enumerate() {
+                let angle = i as f64 * 2.0 * std::f64::consts::PI / lidar.rays() as f64;
+                let distance = reading * self.lidar_range;
+                plot_ui.line(
+                    Line::new(vec![
+                        [state.x(), state.y()],
+                        [state.x() + distance * angle.cos(), state.y() + distance * angle.sin()],
+                    ])
+                    .color(Color32::from_rgba_unmultiplied(255, 80, 80, 80)),
+                );
+            }
+        }
         // Plot a small circle around the spawn centers (if exist) with radius spawn_radius_max
         if let Some((start, goal)) = self.spawn_centers {
 
@@ -546,3 +805,17 @@ impl RenderableEnvironment for PointEnv {
         }
     }
 }
+
+impl RecordableEnvironment for PointEnv {
+    /// The raw agent and goal positions, i.e. the [PointEnv::history] of the
+    /// episode, and the number of currently closed walls.
+    fn step_info(&self) -> Vec<(String, f64)> {
+        vec![
+            ("state_x".to_string(), self.state.x()),
+            ("state_y".to_string(), self.state.y()),
+            ("goal_x".to_string(), self.goal.x()),
+            ("goal_y".to_string(), self.goal.y()),
+            ("walls".to_string(), self.walls.len() as f64),
+        ]
+    }
+}
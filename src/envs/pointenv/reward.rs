@@ -2,6 +2,7 @@ use {
     super::{
         super::DistanceMeasure,
         line::PointLine,
+        obstacle::PointObstacle,
         point_env::reachable,
         state::PointState,
     },
@@ -38,7 +39,7 @@ use {
 /// let height = 10;
 ///
 /// assert_eq!(
-///     reward_mode.compute(&state, &goal, term_radius, &walls),
+///     reward_mode.compute(&state, &goal, term_radius, &walls, &[]),
 ///     -2.0_f64.sqrt(),
 /// );
 /// assert_eq!(
@@ -56,7 +57,7 @@ use {
 /// let height = 10;
 ///
 /// assert_eq!(
-///     reward_mode.compute(&state, &goal, term_radius, &walls),
+///     reward_mode.compute(&state, &goal, term_radius, &walls, &[]),
 ///     0.0,
 /// );
 /// assert_eq!(
@@ -80,11 +81,12 @@ impl PointReward {
         goal: &PointState,
         term_radius: f64,
         walls: &[PointLine],
+        obstacles: &[PointObstacle],
     ) -> f64 {
         match self {
             PointReward::Euclidean => -PointState::distance(state, goal),
             PointReward::Sparse => {
-                if reachable(state, goal, term_radius, walls) {
+                if reachable(state, goal, term_radius, walls, obstacles) {
                     0.0
                 } else {
                     -1.0
@@ -0,0 +1,345 @@
+use {
+    super::{
+        super::{
+            Environment,
+            GymAction,
+            GymObservation,
+            RenderableEnvironment,
+            RecordableEnvironment,
+            Step,
+            VectorConvertible,
+        },
+        protocol::{
+            from_bounds,
+            read_message,
+            write_message,
+            Request,
+            Response,
+        },
+    },
+    crate::configs::RenderableConfig,
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    egui_plot::{
+        Bar,
+        BarChart,
+        PlotUi,
+    },
+    std::{
+        io::{
+            BufRead,
+            BufReader,
+            Write,
+        },
+        net::TcpStream,
+        ops::RangeInclusive,
+        process::{
+            Child,
+            Command,
+            Stdio,
+        },
+        sync::{
+            Arc,
+            Mutex,
+        },
+    },
+};
+
+/// How a [RemoteEnv] reaches its server.
+///
+/// * `Process` - Spawn `command` with `args` and talk over its stdin and
+///   stdout. The stderr of the server is inherited, for its logs.
+/// * `Tcp` - Connect to a server listening on `address`, e.g. `"127.0.0.1:7878"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RemoteTransport {
+    Process {
+        command: String,
+        args: Vec<String>,
+    },
+    Tcp {
+        address: String,
+    },
+}
+
+/// The configuration struct for the [RemoteEnv] environment
+///
+/// Everything else about the environment is configured on the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteConfig {
+    pub transport: RemoteTransport,
+}
+impl Default for RemoteConfig {
+    fn default() -> Self {
+        Self {
+            transport: RemoteTransport::Tcp {
+                address: "127.0.0.1:7878".to_owned(),
+            },
+        }
+    }
+}
+impl RemoteConfig {
+    pub fn new(transport: RemoteTransport) -> Self {
+        Self {
+            transport,
+        }
+    }
+}
+impl RenderableConfig for RemoteConfig {
+    fn render_immutable(
+        &self,
+        ui: &mut egui::Ui,
+    ) {
+        ui.label("RemoteEnv");
+        match &self.transport {
+            RemoteTransport::Process { command, args } => {
+                ui.label(format!("process: {command} {}", args.join(" ")));
+            },
+            RemoteTransport::Tcp { address } => {
+                ui.label(format!("tcp: {address}"));
+            },
+        }
+    }
+
+    fn render_mutable(
+        &mut self,
+        ui: &mut egui::Ui,
+    ) {
+        self.render_immutable(ui);
+    }
+}
+
+/// An open session with a server.
+struct Connection {
+    reader: Box<dyn BufRead + Send>,
+    writer: Box<dyn Write + Send>,
+    child: Option<Child>,
+}
+impl Connection {
+    fn open(transport: &RemoteTransport) -> Result<Self> {
+        match transport {
+            RemoteTransport::Process { command, args } => {
+                let mut child = Command::new(command)
+                    .args(args)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::inherit())
+                    .spawn()?;
+                let reader = child.stdout.take().ok_or_else(|| anyhow!("No stdout of {command}"))?;
+                let writer = child.stdin.take().ok_or_else(|| anyhow!("No stdin of {command}"))?;
+                Ok(Self {
+                    reader: Box::new(BufReader::new(reader)),
+                    writer: Box::new(writer),
+                    child: Some(child),
+                })
+            },
+            RemoteTransport::Tcp { address } => {
+                let stream = TcpStream::connect(address)?;
+                stream.set_nodelay(true)?;
+                Ok(Self {
+                    reader: Box::new(BufReader::new(stream.try_clone()?)),
+                    writer: Box::new(stream),
+                    child: None,
+                })
+            },
+        }
+    }
+
+    /// Send a request and wait for its response, turning a
+    /// [Response::Error] into an error.
+    fn request(
+        &mut self,
+        request: &Request,
+    ) -> Result<Response> {
+        write_message(&mut self.writer, request)?;
+        match read_message(&mut self.reader)? {
+            Some(Response::Error { message }) => Err(anyhow!("The server failed: {message}")),
+            Some(response) => Ok(response),
+            None => Err(anyhow!("The server closed the connection")),
+        }
+    }
+}
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // the server may already be gone, which is fine when closing
+        let _ = write_message(&mut self.writer, &Request::Close);
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.wait();
+        }
+    }
+}
+
+fn unexpected(response: Response) -> anyhow::Error {
+    anyhow!("Unexpected response from the server: {response:?}")
+}
+
+/// An environment that runs in another process, possibly on another machine,
+/// and is reached over the protocol described in the
+/// [module documentation](super).
+///
+/// The observations and actions are the flat vectors also used by
+/// [`GymEnv`](super::super::GymEnv). Actions are sent as given by the agent,
+/// and the server applies the preprocessing of its environment. The
+/// [`step_info`](RecordableEnvironment::step_info) of the server environment
+/// is forwarded. Clones share the session with the server, as clones of the
+/// gymnasium environments share their Python environment.
+#[derive(Clone)]
+pub struct RemoteEnv {
+    config: RemoteConfig,
+    connection: Arc<Mutex<Connection>>,
+    action_space: Vec<usize>,
+    action_domain: Vec<RangeInclusive<f64>>,
+    observation_space: Vec<usize>,
+    observation_domain: Vec<RangeInclusive<f64>>,
+    timelimit: usize,
+    value_range: (f64, f64),
+    current_observation: GymObservation,
+    info: Vec<(String, f64)>,
+}
+impl RemoteEnv {
+    fn request(
+        &self,
+        request: &Request,
+    ) -> Result<Response> {
+        self.connection
+            .lock()
+            .map_err(|_| anyhow!("The connection to the server was poisoned"))?
+            .request(request)
+    }
+}
+
+impl Environment for RemoteEnv {
+    type Config = RemoteConfig;
+    type Action = GymAction;
+    type Observation = GymObservation;
+
+    /// Connect to the server and ask it for the spaces of the environment.
+    fn new(config: Self::Config) -> Result<Box<Self>> {
+        let mut connection = Connection::open(&config.transport)?;
+        let Response::Spaces {
+            action_space,
+            action_domain,
+            observation_space,
+            observation_domain,
+            timelimit,
+            value_range,
+        } = connection.request(&Request::Spaces)?
+        else {
+            return Err(anyhow!("The server did not describe its spaces"));
+        };
+        let mut env = Self {
+            config,
+            connection: Arc::new(Mutex::new(connection)),
+            action_space,
+            action_domain: from_bounds(&action_domain),
+            observation_space,
+            observation_domain: from_bounds(&observation_domain),
+            timelimit,
+            value_range,
+            current_observation: GymObservation(Vec::new()),
+            info: Vec::new(),
+        };
+        env.reset(0)?;
+        Ok(Box::new(env))
+    }
+
+    /// Reset the environment on the server with the given seed
+    fn reset(
+        &mut self,
+        seed: u64,
+    ) -> Result<Self::Observation> {
+        match self.request(&Request::Reset { seed })? {
+            Response::Observation { observation, info } => {
+                self.current_observation = GymObservation::from_vec(observation);
+                self.info = info;
+                Ok(self.current_observation())
+            },
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Step the environment on the server with the given action
+    fn step(
+        &mut self,
+        action: Self::Action,
+    ) -> Result<Step<Self::Observation, Self::Action>> {
+        let request = Request::Step {
+            action: GymAction::to_vec(action.clone()),
+        };
+        match self.request(&request)? {
+            Response::Step { observation, reward, terminated, truncated, info } => {
+                self.current_observation = GymObservation::from_vec(observation);
+                self.info = info;
+                Ok(Step {
+                    observation: self.current_observation(),
+                    action,
+                    reward,
+                    terminated,
+                    truncated,
+                })
+            },
+            response => Err(unexpected(response)),
+        }
+    }
+
+    fn timelimit(&self) -> usize {
+        self.timelimit
+    }
+
+    fn action_space(&self) -> Vec<usize> {
+        self.action_space.clone()
+    }
+
+    fn action_domain(&self) -> Vec<RangeInclusive<f64>> {
+        self.action_domain.clone()
+    }
+
+    fn observation_space(&self) -> Vec<usize> {
+        self.observation_space.clone()
+    }
+
+    fn observation_domain(&self) -> Vec<RangeInclusive<f64>> {
+        self.observation_domain.clone()
+    }
+
+    fn current_observation(&self) -> Self::Observation {
+        self.current_observation.clone()
+    }
+
+    fn value_range(&self) -> (f64, f64) {
+        self.value_range
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+}
+
+impl RenderableEnvironment for RemoteEnv {
+    /// Draw the values of the current observation as bars, since the
+    /// environment itself is only known to the server.
+    fn render(
+        &mut self,
+        plot_ui: &mut PlotUi,
+    ) {
+        let bars = self
+            .current_observation
+            .0
+            .iter()
+            .enumerate()
+            .map(|(i, value)| Bar::new(i as f64, **value))
+            .collect();
+        plot_ui.bar_chart(BarChart::new(bars).name("observation"));
+    }
+}
+
+impl RecordableEnvironment for RemoteEnv {
+    fn step_info(&self) -> Vec<(String, f64)> {
+        self.info.clone()
+    }
+}
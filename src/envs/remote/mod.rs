@@ -0,0 +1,46 @@
+//! Environments in another process, reached over a JSON-lines protocol.
+//!
+//! A [RemoteEnv] is an [Environment](super::Environment) whose simulation runs
+//! in a server, either a child process that talks over its stdin and stdout or
+//! a server listening on TCP. External simulators, such as gymnasium in a
+//! Python process, can be used this way without linking them into the
+//! training binary, and crashes of the simulator don't take the run down.
+//!
+//! Every message is one line of JSON, tagged by its `type`. The client sends a
+//! request and the server answers it with exactly one response:
+//!
+//! | Request                              | Response |
+//! |--------------------------------------|----------|
+//! | `{"type":"spaces"}`                  | `{"type":"spaces","action_space":[2],"action_domain":[[-1.0,1.0],[-1.0,1.0]],"observation_space":[4],"observation_domain":[[0.0,5.0],[null,null],...],"timelimit":30,"value_range":[-30.0,12.0]}` |
+//! | `{"type":"reset","seed":42}`         | `{"type":"observation","observation":[...],"info":[["state_x",0.5],...]}` |
+//! | `{"type":"step","action":[0.1,-0.3]}`| `{"type":"step","observation":[...],"reward":-1.0,"terminated":false,"truncated":false,"info":[...]}` |
+//! | `{"type":"close"}`                   | none, the session ends |
+//!
+//! Any request can instead be answered with `{"type":"error","message":"..."}`,
+//! after which the session continues, e.g. for actions of the wrong length.
+//! The `action_domain` holds one range per component of the action, unbounded
+//! values of a domain are sent as `null`, and `info` holds the
+//! [`step_info`](super::RecordableEnvironment::step_info) of the environment.
+//!
+//! [serve] and [serve_tcp] implement the server side for any environment of
+//! this crate; the `pointenv_server` example serves a
+//! [`PointEnv`](super::PointEnv) with them.
+mod client;
+mod protocol;
+mod server;
+
+pub use {
+    client::{
+        RemoteConfig,
+        RemoteEnv,
+        RemoteTransport,
+    },
+    protocol::{
+        Request,
+        Response,
+    },
+    server::{
+        serve,
+        serve_tcp,
+    },
+};
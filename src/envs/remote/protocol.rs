@@ -0,0 +1,117 @@
+use {
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    std::{
+        io::{
+            BufRead,
+            Write,
+        },
+        ops::RangeInclusive,
+    },
+};
+
+/// The bounds of a single value, where `None` is unbounded. JSON has no
+/// infinite numbers, so infinite bounds are sent as `null`.
+pub type Bounds = (Option<f64>, Option<f64>);
+
+/// A request from a [RemoteEnv](super::RemoteEnv) to a server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    /// Describe the spaces of the environment, answered by [Response::Spaces].
+    Spaces,
+    /// Reset the environment, answered by [Response::Observation].
+    Reset {
+        seed: u64,
+    },
+    /// Step the environment, answered by [Response::Step].
+    Step {
+        action: Vec<f64>,
+    },
+    /// End the session, which is not answered.
+    Close,
+}
+
+/// A response from a server to a [Request].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    Spaces {
+        action_space: Vec<usize>,
+        action_domain: Vec<Bounds>,
+        observation_space: Vec<usize>,
+        observation_domain: Vec<Bounds>,
+        timelimit: usize,
+        value_range: (f64, f64),
+    },
+    Observation {
+        observation: Vec<f64>,
+        info: Vec<(String, f64)>,
+    },
+    Step {
+        observation: Vec<f64>,
+        reward: f64,
+        terminated: bool,
+        truncated: bool,
+        info: Vec<(String, f64)>,
+    },
+    /// The request failed on the server, but the session continues.
+    Error {
+        message: String,
+    },
+}
+
+pub fn to_bounds(domain: &[RangeInclusive<f64>]) -> Vec<Bounds> {
+    domain
+        .iter()
+        .map(|range| {
+            (
+                range.start().is_finite().then_some(*range.start()),
+                range.end().is_finite().then_some(*range.end()),
+            )
+        })
+        .collect()
+}
+
+pub fn from_bounds(bounds: &[Bounds]) -> Vec<RangeInclusive<f64>> {
+    bounds
+        .iter()
+        .map(|(lo, hi)| lo.unwrap_or(f64::NEG_INFINITY)..=hi.unwrap_or(f64::INFINITY))
+        .collect()
+}
+
+/// Write a message as a single line of JSON.
+pub fn write_message<M: Serialize>(
+    writer: &mut dyn Write,
+    message: &M,
+) -> Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read a message from a single line of JSON, or `None` once the other side
+/// has closed the connection.
+pub fn read_message<M: for<'a> Deserialize<'a>>(reader: &mut dyn BufRead) -> Result<Option<M>> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if !line.trim().is_empty() {
+            break;
+        }
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| anyhow!("Invalid message {:?}: {e}", line.trim()))
+}
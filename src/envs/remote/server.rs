@@ -0,0 +1,168 @@
+use {
+    super::{
+        super::{
+            Environment,
+            RecordableEnvironment,
+            VectorConvertible,
+        },
+        protocol::{
+            read_message,
+            to_bounds,
+            write_message,
+            Request,
+            Response,
+        },
+    },
+    anyhow::Result,
+    std::{
+        io::{
+            BufRead,
+            BufReader,
+            Write,
+        },
+        net::{
+            TcpListener,
+            ToSocketAddrs,
+        },
+        ops::RangeInclusive,
+        thread,
+    },
+    tracing::warn,
+};
+
+/// The domain of the actions with one range per component, as the client
+/// samples every component from its own range.
+///
+/// Environments whose domain has a different shape, like the single step radius
+/// of a [`PointEnv`](super::super::PointEnv), are bounded by the largest
+/// magnitude of their domain in every component, and the environment restricts
+/// the actions further when it is stepped.
+fn component_domain(
+    domain: &[RangeInclusive<f64>],
+    size: usize,
+) -> Vec<RangeInclusive<f64>> {
+    if domain.len() == size {
+        return domain.to_vec();
+    }
+    let bound = domain
+        .iter()
+        .map(|range| range.start().abs().max(range.end().abs()))
+        .fold(0.0, f64::max);
+    vec![-bound..=bound; size]
+}
+
+/// Answer a single request with the environment. Failures of the environment
+/// are answered with [Response::Error] instead of ending the session.
+fn respond<Env>(
+    env: &mut Env,
+    request: Request,
+) -> Response
+where
+    Env: Environment + RecordableEnvironment,
+    Env::Observation: VectorConvertible,
+    Env::Action: VectorConvertible,
+{
+    let size_action = env.action_space().iter().product::<usize>();
+    let result = match request {
+        Request::Spaces => Ok(Response::Spaces {
+            action_space: env.action_space(),
+            action_domain: to_bounds(&component_domain(&env.action_domain(), size_action)),
+            observation_space: env.observation_space(),
+            observation_domain: to_bounds(&env.observation_domain()),
+            timelimit: env.timelimit(),
+            value_range: env.value_range(),
+        }),
+        Request::Reset { seed } => env.reset(seed).map(|observation| Response::Observation {
+            observation: <Env::Observation>::to_vec(observation),
+            info: env.step_info(),
+        }),
+        Request::Step { action } if action.len() != size_action => Err(anyhow::anyhow!(
+            "Expected an action with {size_action} values but got {}",
+            action.len(),
+        )),
+        // the action is preprocessed as it would be in a local training loop
+        Request::Step { action } => env.step(<Env::Action>::from_vec_pp(action)).map(|step| Response::Step {
+            observation: <Env::Observation>::to_vec(step.observation),
+            reward: step.reward,
+            terminated: step.terminated,
+            truncated: step.truncated,
+            info: env.step_info(),
+        }),
+        Request::Close => Err(anyhow::anyhow!("Close is handled by the session")),
+    };
+    result.unwrap_or_else(|e| Response::Error { message: e.to_string() })
+}
+
+/// Serve the environment to a single client until it sends [Request::Close]
+/// or closes the connection.
+///
+/// This is the server side of the protocol described in the
+/// [module documentation](super), and `reader` and `writer` can be anything
+/// from stdin and stdout to a TCP stream. Actions are preprocessed with
+/// [`VectorConvertible::from_vec_pp`], like the actions of an agent in a local
+/// training loop.
+pub fn serve<Env>(
+    env: &mut Env,
+    reader: &mut dyn BufRead,
+    writer: &mut dyn Write,
+) -> Result<()>
+where
+    Env: Environment + RecordableEnvironment,
+    Env::Observation: VectorConvertible,
+    Env::Action: VectorConvertible,
+{
+    loop {
+        let request = match read_message::<Request>(reader) {
+            Ok(Some(Request::Close)) | Ok(None) => return Ok(()),
+            Ok(Some(request)) => request,
+            Err(e) => {
+                write_message(writer, &Response::Error { message: e.to_string() })?;
+                continue;
+            },
+        };
+        write_message(writer, &respond(env, request))?;
+    }
+}
+
+/// Serve the environment over TCP, to any number of clients at once.
+///
+/// Every client is served on a thread of its own with a new environment
+/// created from `config`, so sessions don't influence or wait for each other.
+/// Failed sessions are logged and the server keeps accepting clients.
+pub fn serve_tcp<Env, A>(
+    config: Env::Config,
+    address: A,
+) -> Result<()>
+where
+    Env: Environment + RecordableEnvironment + 'static,
+    Env::Config: Clone + Send + 'static,
+    Env::Observation: VectorConvertible,
+    Env::Action: VectorConvertible,
+    A: ToSocketAddrs,
+{
+    let listener = TcpListener::bind(address)?;
+    warn!("serving on {}", listener.local_addr()?);
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("failed to accept a client: {e}");
+                continue;
+            },
+        };
+        let config = config.clone();
+        thread::spawn(move || {
+            let session = (|| -> Result<()> {
+                warn!("client connected from {}", stream.peer_addr()?);
+                let mut env = *Env::new(config)?;
+                let mut reader = BufReader::new(stream.try_clone()?);
+                let mut writer = stream;
+                serve(&mut env, &mut reader, &mut writer)
+            })();
+            if let Err(e) = session {
+                warn!("session failed: {e}");
+            }
+        });
+    }
+    Ok(())
+}
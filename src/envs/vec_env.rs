@@ -0,0 +1,145 @@
+use {
+    super::{
+        Environment,
+        TensorConvertible,
+    },
+    anyhow::Result,
+    candle_core::{
+        Device,
+        Tensor,
+    },
+    rand::{
+        Rng,
+        RngCore,
+    },
+};
+
+/// The result of stepping all environments of a [VecEnv] at once.
+///
+/// The entries are in the order of the environments. The `observations` are
+/// the ones returned by the steps, i.e. the final observations of any finished
+/// episodes, and not the first observations of the episodes that replace them.
+#[derive(Debug)]
+pub struct VecStep<O, A> {
+    pub observations: Vec<O>,
+    pub actions: Vec<A>,
+    pub rewards: Vec<f64>,
+    pub terminated: Vec<bool>,
+    pub truncated: Vec<bool>,
+}
+impl<O, A> VecStep<O, A> {
+    /// Whether the episode of the environment at `index` has finished with this step.
+    pub fn done(
+        &self,
+        index: usize,
+    ) -> bool {
+        self.terminated[index] || self.truncated[index]
+    }
+}
+
+/// A batch of `N` environments of the same kind, which are stepped together.
+///
+/// Observations and actions are exchanged as batched [Tensor]s of shape
+/// `[N, ...]`, so an agent can select the actions for all environments at
+/// once. Environments whose episodes have finished are reset automatically
+/// with seeds drawn from the given random number generator.
+pub struct VecEnv<Env: Environment> {
+    envs: Vec<Box<Env>>,
+}
+impl<Env> VecEnv<Env>
+where
+    Env: Environment,
+    Env::Observation: Clone + TensorConvertible,
+    Env::Action: Clone + TensorConvertible,
+{
+    pub fn new(envs: Vec<Box<Env>>) -> Self {
+        assert!(!envs.is_empty(), "A VecEnv needs at least one environment");
+        Self { envs }
+    }
+
+    /// Create `n` environments from clones of the same config.
+    pub fn from_config(
+        config: Env::Config,
+        n: usize,
+    ) -> Result<Self>
+    where
+        Env::Config: Clone,
+    {
+        let envs = (0..n)
+            .map(|_| Env::new(config.clone()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(envs))
+    }
+
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+
+    pub fn envs(&self) -> &[Box<Env>] {
+        &self.envs
+    }
+
+    pub fn envs_mut(&mut self) -> &mut [Box<Env>] {
+        &mut self.envs
+    }
+
+    /// Reset all environments, each with its own seed from `rng`.
+    pub fn reset(
+        &mut self,
+        rng: &mut dyn RngCore,
+    ) -> Result<Vec<Env::Observation>> {
+        self.envs
+            .iter_mut()
+            .map(|env| env.reset(rng.gen::<u64>()))
+            .collect()
+    }
+
+    /// The current observations of all environments as a `[N, ...]` tensor.
+    pub fn observations(
+        &self,
+        device: &Device,
+    ) -> Result<Tensor> {
+        let observations = self
+            .envs
+            .iter()
+            .map(|env| <Env::Observation>::to_tensor(env.current_observation(), device))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        Ok(Tensor::stack(&observations, 0)?)
+    }
+
+    /// Step every environment with its row of the `[N, ...]` `actions` tensor.
+    ///
+    /// Environments whose episodes finish with this step are reset right away,
+    /// so [`VecEnv::observations`] always returns ongoing episodes.
+    pub fn step(
+        &mut self,
+        actions: &Tensor,
+        rng: &mut dyn RngCore,
+    ) -> Result<VecStep<Env::Observation, Env::Action>> {
+        let n = self.envs.len();
+        let mut step = VecStep {
+            observations: Vec::with_capacity(n),
+            actions: Vec::with_capacity(n),
+            rewards: Vec::with_capacity(n),
+            terminated: Vec::with_capacity(n),
+            truncated: Vec::with_capacity(n),
+        };
+
+        for (i, env) in self.envs.iter_mut().enumerate() {
+            let env_step = env.step(<Env::Action>::from_tensor_pp(actions.get(i)?))?;
+            if env_step.terminated || env_step.truncated {
+                env.reset(rng.gen::<u64>())?;
+            }
+            step.observations.push(env_step.observation);
+            step.actions.push(env_step.action);
+            step.rewards.push(env_step.reward);
+            step.terminated.push(env_step.terminated);
+            step.truncated.push(env_step.truncated);
+        }
+        Ok(step)
+    }
+}
@@ -0,0 +1,81 @@
+use {
+    super::{
+        super::{
+            Environment,
+            Step,
+        },
+        EnvWrapper,
+    },
+    anyhow::Result,
+};
+
+/// Repeat every action for a number of steps of the inner environment.
+///
+/// The rewards of the repeated steps are summed up, and the repetition stops
+/// early when the episode ends. The returned observation is the last one.
+///
+/// The config is the number of repetitions.
+#[derive(Debug, Clone)]
+pub struct ActionRepeat {
+    repeat: usize,
+}
+
+impl<Env> EnvWrapper<Env> for ActionRepeat
+where
+    Env: Environment,
+    Env::Action: Clone,
+{
+    type Config = usize;
+    type Observation = Env::Observation;
+
+    fn new(
+        config: &Self::Config,
+        _env: &Env,
+    ) -> Result<Self> {
+        if *config == 0 {
+            return Err(anyhow::anyhow!("Actions must be repeated at least once"));
+        }
+        Ok(Self { repeat: *config })
+    }
+
+    fn reset(
+        &mut self,
+        env: &mut Env,
+        seed: u64,
+    ) -> Result<Self::Observation> {
+        env.reset(seed)
+    }
+
+    fn step(
+        &mut self,
+        env: &mut Env,
+        action: Env::Action,
+    ) -> Result<Step<Self::Observation, Env::Action>> {
+        let mut step = env.step(action.clone())?;
+        let mut reward = step.reward;
+        for _ in 1..self.repeat {
+            if step.terminated || step.truncated {
+                break;
+            }
+            step = env.step(action.clone())?;
+            reward += step.reward;
+        }
+        step.reward = reward;
+        step.action = action;
+        Ok(step)
+    }
+
+    fn current_observation(
+        &self,
+        env: &Env,
+    ) -> Self::Observation {
+        env.current_observation()
+    }
+
+    fn timelimit(
+        &self,
+        env: &Env,
+    ) -> usize {
+        env.timelimit().div_ceil(self.repeat)
+    }
+}
@@ -0,0 +1,97 @@
+use {
+    super::{
+        super::{
+            Environment,
+            Step,
+            VectorConvertible,
+        },
+        EnvWrapper,
+    },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    anyhow::Result,
+};
+
+/// Clip the actions to the `action_domain` of the inner environment before
+/// passing them on.
+///
+/// # Variants
+/// * `Components` - Clamp every component to its range of the domain. A
+///   domain with a single range applies it to all components.
+/// * `Norm` - Scale the action down to a length of at most the end of the
+///   single range of the domain, for domains that give a radius like the one
+///   of the [`PointEnv`](super::super::PointEnv).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ClipAction {
+    Components,
+    Norm,
+}
+impl ClipAction {
+    pub fn clip(
+        &self,
+        mut action: Vec<f64>,
+        domain: &[std::ops::RangeInclusive<f64>],
+    ) -> Vec<f64> {
+        if domain.is_empty() {
+            return action;
+        }
+        match self {
+            Self::Components => {
+                for (i, value) in action.iter_mut().enumerate() {
+                    let range = &domain[i.min(domain.len() - 1)];
+                    *value = value.clamp(*range.start(), *range.end());
+                }
+            },
+            Self::Norm => {
+                let radius = *domain[0].end();
+                let norm = action.iter().map(|v| v * v).sum::<f64>().sqrt();
+                if norm > radius {
+                    action.iter_mut().for_each(|v| *v *= radius / norm);
+                }
+            },
+        }
+        action
+    }
+}
+
+impl<Env> EnvWrapper<Env> for ClipAction
+where
+    Env: Environment,
+    Env::Action: VectorConvertible,
+{
+    type Config = Self;
+    type Observation = Env::Observation;
+
+    fn new(
+        config: &Self::Config,
+        _env: &Env,
+    ) -> Result<Self> {
+        Ok(*config)
+    }
+
+    fn reset(
+        &mut self,
+        env: &mut Env,
+        seed: u64,
+    ) -> Result<Self::Observation> {
+        env.reset(seed)
+    }
+
+    fn step(
+        &mut self,
+        env: &mut Env,
+        action: Env::Action,
+    ) -> Result<Step<Self::Observation, Env::Action>> {
+        let clipped = self.clip(<Env::Action>::to_vec(action), &env.action_domain());
+        env.step(<Env::Action>::from_vec(clipped))
+    }
+
+    fn current_observation(
+        &self,
+        env: &Env,
+    ) -> Self::Observation {
+        env.current_observation()
+    }
+}
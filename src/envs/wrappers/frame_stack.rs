@@ -0,0 +1,98 @@
+use {
+    super::{
+        super::{
+            Environment,
+            Step,
+            VectorConvertible,
+        },
+        EnvWrapper,
+        VectorObservation,
+    },
+    anyhow::Result,
+    std::collections::VecDeque,
+};
+
+/// Stack the observation vectors of the last `frames` steps, oldest first.
+///
+/// After a reset, the stack is filled with copies of the first observation.
+///
+/// The config is the number of frames.
+#[derive(Debug, Clone)]
+pub struct FrameStack {
+    frames: VecDeque<Vec<f64>>,
+}
+impl FrameStack {
+    fn push(
+        &mut self,
+        frame: Vec<f64>,
+    ) -> VectorObservation {
+        self.frames.pop_front();
+        self.frames.push_back(frame);
+        self.stacked()
+    }
+
+    fn stacked(&self) -> VectorObservation {
+        VectorObservation(self.frames.iter().flatten().copied().collect())
+    }
+}
+
+impl<Env> EnvWrapper<Env> for FrameStack
+where
+    Env: Environment,
+    Env::Observation: VectorConvertible,
+{
+    type Config = usize;
+    type Observation = VectorObservation;
+
+    fn new(
+        config: &Self::Config,
+        env: &Env,
+    ) -> Result<Self> {
+        if *config == 0 {
+            return Err(anyhow::anyhow!("At least one frame must be stacked"));
+        }
+        let frame = <Env::Observation>::to_vec(env.current_observation());
+        Ok(Self {
+            frames: vec![frame; *config].into(),
+        })
+    }
+
+    fn reset(
+        &mut self,
+        env: &mut Env,
+        seed: u64,
+    ) -> Result<Self::Observation> {
+        let frame = <Env::Observation>::to_vec(env.reset(seed)?);
+        self.frames.iter_mut().for_each(|f| f.clone_from(&frame));
+        Ok(self.stacked())
+    }
+
+    fn step(
+        &mut self,
+        env: &mut Env,
+        action: Env::Action,
+    ) -> Result<Step<Self::Observation, Env::Action>> {
+        let step = env.step(action)?;
+        Ok(Step {
+            observation: self.push(<Env::Observation>::to_vec(step.observation)),
+            action: step.action,
+            reward: step.reward,
+            terminated: step.terminated,
+            truncated: step.truncated,
+        })
+    }
+
+    fn current_observation(
+        &self,
+        _env: &Env,
+    ) -> Self::Observation {
+        self.stacked()
+    }
+
+    fn observation_space(
+        &self,
+        env: &Env,
+    ) -> Vec<usize> {
+        vec![self.frames.len() * env.observation_space().iter().product::<usize>()]
+    }
+}
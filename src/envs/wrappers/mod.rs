@@ -0,0 +1,324 @@
+//! Composable wrappers around any [Environment].
+//!
+//! A [Wrapper] combines an inner environment with an [EnvWrapper] that
+//! transforms its observations, actions, rewards or episode boundaries, and is
+//! an [Environment] itself, so wrappers can be stacked. Rendering is forwarded
+//! to the innermost environment.
+//!
+//! [RecordEpisodes] writes every step of the wrapped environment to parquet
+//! files, to analyze the trajectories behind the returns of a run.
+mod action_repeat;
+mod clip_action;
+mod frame_stack;
+mod normalize;
+mod record;
+mod reward;
+mod time_limit;
+
+pub use {
+    action_repeat::ActionRepeat,
+    clip_action::ClipAction,
+    frame_stack::FrameStack,
+    normalize::{
+        NormalizeObservation,
+        NormalizeConfig,
+    },
+    record::{
+        RecordEpisodes,
+        RecordConfig,
+    },
+    reward::TransformReward,
+    time_limit::TimeLimit,
+};
+
+use {
+    super::{
+        Environment,
+        RenderableEnvironment,
+        RecordableEnvironment,
+        Step,
+        TensorConvertible,
+        VectorConvertible,
+    },
+    anyhow::Result,
+    candle_core::{
+        Device,
+        Tensor,
+    },
+    egui_plot::PlotUi,
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    std::ops::RangeInclusive,
+};
+
+/// A transformation of an inner environment of type `Env`, applied by a [Wrapper].
+///
+/// Every method gets access to the inner environment and defaults to passing
+/// the call through unchanged, so a wrapper only implements what it changes.
+pub trait EnvWrapper<Env: Environment>: Sized {
+    type Config: Clone;
+    type Observation;
+
+    fn new(
+        config: &Self::Config,
+        env: &Env,
+    ) -> Result<Self>;
+
+    fn reset(
+        &mut self,
+        env: &mut Env,
+        seed: u64,
+    ) -> Result<Self::Observation>;
+
+    fn step(
+        &mut self,
+        env: &mut Env,
+        action: Env::Action,
+    ) -> Result<Step<Self::Observation, Env::Action>>;
+
+    fn current_observation(
+        &self,
+        env: &Env,
+    ) -> Self::Observation;
+
+    fn timelimit(
+        &self,
+        env: &Env,
+    ) -> usize {
+        env.timelimit()
+    }
+
+    fn action_domain(
+        &self,
+        env: &Env,
+    ) -> Vec<RangeInclusive<f64>> {
+        env.action_domain()
+    }
+
+    fn observation_space(
+        &self,
+        env: &Env,
+    ) -> Vec<usize> {
+        env.observation_space()
+    }
+
+    fn observation_domain(
+        &self,
+        env: &Env,
+    ) -> Vec<RangeInclusive<f64>> {
+        env.observation_domain()
+    }
+
+    fn value_range(
+        &self,
+        env: &Env,
+    ) -> (f64, f64) {
+        env.value_range()
+    }
+}
+
+/// The config of a [Wrapper], i.e. the config of the inner environment and
+/// the config of the [EnvWrapper] around it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WrapperConfig<E, W> {
+    pub env: E,
+    pub wrapper: W,
+}
+impl<E, W> WrapperConfig<E, W> {
+    pub fn new(
+        env: E,
+        wrapper: W,
+    ) -> Self {
+        Self {
+            env,
+            wrapper,
+        }
+    }
+}
+
+/// An [Environment] made of an inner environment and an [EnvWrapper].
+///
+/// # Example
+/// ```ignore
+/// // a PointEnv with normalized, stacked observations and a shorter timelimit
+/// type Wrapped = Wrapper<Wrapper<Wrapper<PointEnv, TimeLimit>, NormalizeObservation>, FrameStack>;
+/// ```
+pub struct Wrapper<Env: Environment, W: EnvWrapper<Env>> {
+    env: Box<Env>,
+    wrapper: W,
+    config: WrapperConfig<Env::Config, W::Config>,
+}
+impl<Env, W> Clone for Wrapper<Env, W>
+where
+    Env: Environment + Clone,
+    Env::Config: Clone,
+    W: EnvWrapper<Env> + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            env: self.env.clone(),
+            wrapper: self.wrapper.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+impl<Env: Environment, W: EnvWrapper<Env>> Wrapper<Env, W> {
+    /// Wrap an existing environment.
+    pub fn wrap(
+        env: Box<Env>,
+        config: WrapperConfig<Env::Config, W::Config>,
+    ) -> Result<Self> {
+        let wrapper = W::new(&config.wrapper, &env)?;
+        Ok(Self {
+            env,
+            wrapper,
+            config,
+        })
+    }
+
+    pub fn inner(&self) -> &Env {
+        &self.env
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Env {
+        &mut self.env
+    }
+
+    pub fn wrapper(&self) -> &W {
+        &self.wrapper
+    }
+
+    pub fn wrapper_mut(&mut self) -> &mut W {
+        &mut self.wrapper
+    }
+}
+
+impl<Env, W> Environment for Wrapper<Env, W>
+where
+    Env: Environment,
+    Env::Config: Clone,
+    W: EnvWrapper<Env>,
+{
+    type Config = WrapperConfig<Env::Config, W::Config>;
+    type Action = Env::Action;
+    type Observation = W::Observation;
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn new(config: Self::Config) -> Result<Box<Self>> {
+        let env = Env::new(config.env.clone())?;
+        Ok(Box::new(Self::wrap(env, config)?))
+    }
+
+    fn seed_config(
+        config: &mut Self::Config,
+        seed: u64,
+    ) {
+        Env::seed_config(&mut config.env, seed);
+    }
+
+    fn reset(
+        &mut self,
+        seed: u64,
+    ) -> Result<Self::Observation> {
+        self.wrapper.reset(&mut self.env, seed)
+    }
+
+    fn step(
+        &mut self,
+        action: Self::Action,
+    ) -> Result<Step<Self::Observation, Self::Action>> {
+        self.wrapper.step(&mut self.env, action)
+    }
+
+    fn timelimit(&self) -> usize {
+        self.wrapper.timelimit(&self.env)
+    }
+
+    fn action_space(&self) -> Vec<usize> {
+        self.env.action_space()
+    }
+
+    fn action_domain(&self) -> Vec<RangeInclusive<f64>> {
+        self.wrapper.action_domain(&self.env)
+    }
+
+    fn observation_space(&self) -> Vec<usize> {
+        self.wrapper.observation_space(&self.env)
+    }
+
+    fn observation_domain(&self) -> Vec<RangeInclusive<f64>> {
+        self.wrapper.observation_domain(&self.env)
+    }
+
+    fn current_observation(&self) -> Self::Observation {
+        self.wrapper.current_observation(&self.env)
+    }
+
+    fn value_range(&self) -> (f64, f64) {
+        self.wrapper.value_range(&self.env)
+    }
+}
+
+impl<Env, W> RenderableEnvironment for Wrapper<Env, W>
+where
+    Env: Environment + RenderableEnvironment,
+    W: EnvWrapper<Env>,
+{
+    fn render(
+        &mut self,
+        plot_ui: &mut PlotUi,
+    ) {
+        self.env.render(plot_ui)
+    }
+}
+
+impl<Env, W> RecordableEnvironment for Wrapper<Env, W>
+where
+    Env: Environment + RecordableEnvironment,
+    W: EnvWrapper<Env>,
+{
+    fn step_info(&self) -> Vec<(String, f64)> {
+        self.env.step_info()
+    }
+}
+
+/// A plain vector observation, for wrappers whose observations no longer
+/// match the observation type of the inner environment, e.g. [FrameStack].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorObservation(pub Vec<f64>);
+
+impl VectorConvertible for VectorObservation {
+    fn from_vec_pp(value: Vec<f64>) -> Self {
+        Self::from_vec(value)
+    }
+
+    fn from_vec(value: Vec<f64>) -> Self {
+        Self(value)
+    }
+
+    fn to_vec(value: Self) -> Vec<f64> {
+        value.0
+    }
+}
+
+impl TensorConvertible for VectorObservation {
+    fn from_tensor_pp(value: Tensor) -> Self {
+        Self::from_tensor(value)
+    }
+
+    fn from_tensor(value: Tensor) -> Self {
+        Self::from_vec(value.to_vec1::<f64>().unwrap())
+    }
+
+    fn to_tensor(
+        value: Self,
+        device: &Device,
+    ) -> candle_core::Result<Tensor> {
+        Tensor::new(Self::to_vec(value), device)
+    }
+}
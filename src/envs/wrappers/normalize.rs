@@ -0,0 +1,154 @@
+use {
+    super::{
+        super::{
+            Environment,
+            Step,
+            VectorConvertible,
+        },
+        EnvWrapper,
+        VectorObservation,
+    },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    anyhow::Result,
+    std::ops::RangeInclusive,
+};
+
+/// The config of [NormalizeObservation].
+///
+/// * `clip` - The normalized values are clipped to `[-clip, clip]`.
+/// * `epsilon` - Added to the variance to avoid divisions by zero.
+/// * `update` - Whether the running statistics are updated with new
+///   observations, e.g. disable it to evaluate with fixed statistics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalizeConfig {
+    pub clip: f64,
+    pub epsilon: f64,
+    pub update: bool,
+}
+impl Default for NormalizeConfig {
+    fn default() -> Self {
+        Self {
+            clip: 10.0,
+            epsilon: 1e-8,
+            update: true,
+        }
+    }
+}
+
+/// Normalize the observation vectors of the inner environment to zero mean
+/// and unit variance per component, using running statistics over all
+/// observations seen so far (Welford's algorithm).
+#[derive(Debug, Clone)]
+pub struct NormalizeObservation {
+    config: NormalizeConfig,
+    count: f64,
+    mean: Vec<f64>,
+    m2: Vec<f64>,
+}
+impl NormalizeObservation {
+    fn update(
+        &mut self,
+        values: &[f64],
+    ) {
+        if !self.config.update {
+            return;
+        }
+        self.count += 1.0;
+        for (i, &value) in values.iter().enumerate() {
+            let delta = value - self.mean[i];
+            self.mean[i] += delta / self.count;
+            self.m2[i] += delta * (value - self.mean[i]);
+        }
+    }
+
+    fn normalize(
+        &self,
+        values: Vec<f64>,
+    ) -> VectorObservation {
+        let clip = self.config.clip;
+        VectorObservation(
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    let var = if self.count > 1.0 { self.m2[i] / self.count } else { 1.0 };
+                    ((value - self.mean[i]) / (var + self.config.epsilon).sqrt()).clamp(-clip, clip)
+                })
+                .collect(),
+        )
+    }
+
+    fn observe(
+        &mut self,
+        values: Vec<f64>,
+    ) -> VectorObservation {
+        self.update(&values);
+        self.normalize(values)
+    }
+}
+
+impl<Env> EnvWrapper<Env> for NormalizeObservation
+where
+    Env: Environment,
+    Env::Observation: VectorConvertible,
+{
+    type Config = NormalizeConfig;
+    type Observation = VectorObservation;
+
+    fn new(
+        config: &Self::Config,
+        env: &Env,
+    ) -> Result<Self> {
+        if !(config.clip > 0.0 && config.epsilon >= 0.0) {
+            return Err(anyhow::anyhow!("The clip must be positive and epsilon non-negative"));
+        }
+        let size = env.observation_space().iter().product::<usize>();
+        Ok(Self {
+            config: config.clone(),
+            count: 0.0,
+            mean: vec![0.0; size],
+            m2: vec![0.0; size],
+        })
+    }
+
+    fn reset(
+        &mut self,
+        env: &mut Env,
+        seed: u64,
+    ) -> Result<Self::Observation> {
+        let observation = env.reset(seed)?;
+        Ok(self.observe(<Env::Observation>::to_vec(observation)))
+    }
+
+    fn step(
+        &mut self,
+        env: &mut Env,
+        action: Env::Action,
+    ) -> Result<Step<Self::Observation, Env::Action>> {
+        let step = env.step(action)?;
+        Ok(Step {
+            observation: self.observe(<Env::Observation>::to_vec(step.observation)),
+            action: step.action,
+            reward: step.reward,
+            terminated: step.terminated,
+            truncated: step.truncated,
+        })
+    }
+
+    fn current_observation(
+        &self,
+        env: &Env,
+    ) -> Self::Observation {
+        self.normalize(<Env::Observation>::to_vec(env.current_observation()))
+    }
+
+    fn observation_domain(
+        &self,
+        _env: &Env,
+    ) -> Vec<RangeInclusive<f64>> {
+        vec![-self.config.clip..=self.config.clip]
+    }
+}
@@ -0,0 +1,289 @@
+use {
+    super::{
+        super::{
+            Environment,
+            RecordableEnvironment,
+            Step,
+            VectorConvertible,
+        },
+        EnvWrapper,
+    },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    anyhow::Result,
+    polars::prelude::{
+        DataFrame,
+        NamedFrom,
+        ParquetWriter,
+        Series,
+    },
+    std::{
+        fs::{
+            create_dir,
+            create_dir_all,
+            OpenOptions,
+        },
+        io::ErrorKind,
+        path::PathBuf,
+        sync::atomic::{
+            AtomicU64,
+            Ordering,
+        },
+    },
+    tracing::warn,
+};
+
+/// The config of [RecordEpisodes].
+///
+/// * `directory` - The directory the recordings are written to, one subdirectory
+///   per wrapper.
+/// * `episodes_per_file` - The number of episodes that are collected before
+///   they are written to a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordConfig {
+    pub directory: String,
+    pub episodes_per_file: usize,
+}
+impl RecordConfig {
+    pub fn new(
+        directory: &str,
+        episodes_per_file: usize,
+    ) -> Self {
+        Self {
+            directory: directory.to_string(),
+            episodes_per_file,
+        }
+    }
+}
+
+/// A single recorded step. The first step of every episode is the reset,
+/// without an action or reward.
+#[derive(Debug, Clone)]
+struct StepRecord {
+    episode: u64,
+    step: u64,
+    observation: Vec<f64>,
+    action: Option<Vec<f64>>,
+    reward: Option<f64>,
+    terminated: bool,
+    truncated: bool,
+    info: Vec<(String, f64)>,
+}
+
+/// Record every step of the inner environment and write the episodes to
+/// parquet files, without changing the environment in any way.
+///
+/// Every row holds the `episode` and `step` indices, the observation vector
+/// (`observation_0`, ...), the action vector (`action_0`, ...), the `reward`,
+/// the `terminated` and `truncated` flags and the
+/// [`step_info`](RecordableEnvironment::step_info) of the environment. The
+/// reset starts every episode as step 0, with empty action and reward.
+///
+/// Every wrapper claims a subdirectory of its own in the configured directory
+/// when it is created, the first of `recording_000`, `recording_001`, ... that
+/// is not taken yet. The episodes are written to `episodes_{first}-{last}.parquet`
+/// files in it, whenever `episodes_per_file` episodes are collected, on
+/// [`RecordEpisodes::flush`] and when the wrapper is dropped. A clone starts
+/// without any of the recorded steps and writes its files to the same
+/// subdirectory with a prefix of its own, e.g. the third clone of the original
+/// uses `clone2_episodes_{first}-{last}.parquet`. Existing files are never
+/// overwritten.
+#[derive(Debug)]
+pub struct RecordEpisodes {
+    config: RecordConfig,
+    directory: PathBuf,
+    prefix: String,
+    clones: AtomicU64,
+    records: Vec<StepRecord>,
+    first_episode: u64,
+    episode: u64,
+    step: u64,
+    started: bool,
+}
+impl RecordEpisodes {
+    /// Write all recorded steps to a file, and start collecting anew.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.records.is_empty() {
+            return Ok(());
+        }
+        let records = std::mem::take(&mut self.records);
+        let last_episode = records.last().map(|r| r.episode).unwrap_or(self.first_episode);
+        let path = self
+            .directory
+            .join(format!("{}episodes_{:06}-{:06}.parquet", self.prefix, self.first_episode, last_episode));
+        self.first_episode = last_episode + 1;
+
+        let mut columns = vec![
+            Series::new("episode", records.iter().map(|r| r.episode).collect::<Vec<_>>()),
+            Series::new("step", records.iter().map(|r| r.step).collect::<Vec<_>>()),
+        ];
+        let n_observation = records.iter().map(|r| r.observation.len()).max().unwrap_or(0);
+        for i in 0..n_observation {
+            columns.push(Series::new(
+                &format!("observation_{i}"),
+                records.iter().map(|r| r.observation.get(i).copied()).collect::<Vec<_>>(),
+            ));
+        }
+        let n_action = records.iter().filter_map(|r| r.action.as_ref().map(Vec::len)).max().unwrap_or(0);
+        for i in 0..n_action {
+            columns.push(Series::new(
+                &format!("action_{i}"),
+                records
+                    .iter()
+                    .map(|r| r.action.as_ref().and_then(|a| a.get(i).copied()))
+                    .collect::<Vec<_>>(),
+            ));
+        }
+        columns.push(Series::new("reward", records.iter().map(|r| r.reward).collect::<Vec<_>>()));
+        columns.push(Series::new("terminated", records.iter().map(|r| r.terminated).collect::<Vec<_>>()));
+        columns.push(Series::new("truncated", records.iter().map(|r| r.truncated).collect::<Vec<_>>()));
+
+        // the info columns in order of their first appearance
+        let mut info_names: Vec<&String> = Vec::new();
+        for (name, _) in records.iter().flat_map(|r| r.info.iter()) {
+            if !info_names.contains(&name) {
+                info_names.push(name);
+            }
+        }
+        for name in info_names {
+            columns.push(Series::new(
+                name,
+                records
+                    .iter()
+                    .map(|r| r.info.iter().find(|(n, _)| n == name).map(|(_, v)| *v))
+                    .collect::<Vec<_>>(),
+            ));
+        }
+
+        let mut df = DataFrame::new(columns)?;
+        let file = OpenOptions::new().write(true).create_new(true).open(&path).map_err(|e| {
+            anyhow::anyhow!("Failed to create {}: {e}", path.display())
+        })?;
+        ParquetWriter::new(file).finish(&mut df)?;
+        Ok(())
+    }
+}
+
+impl Clone for RecordEpisodes {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            directory: self.directory.clone(),
+            prefix: format!("{}clone{}_", self.prefix, self.clones.fetch_add(1, Ordering::Relaxed)),
+            clones: AtomicU64::new(0),
+            records: Vec::new(),
+            first_episode: self.episode,
+            episode: self.episode,
+            step: self.step,
+            started: self.started,
+        }
+    }
+}
+
+impl Drop for RecordEpisodes {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            warn!("Failed to write the recorded episodes: {e}");
+        }
+    }
+}
+
+impl<Env> EnvWrapper<Env> for RecordEpisodes
+where
+    Env: Environment + RecordableEnvironment,
+    Env::Observation: Clone + VectorConvertible,
+    Env::Action: Clone + VectorConvertible,
+{
+    type Config = RecordConfig;
+    type Observation = Env::Observation;
+
+    fn new(
+        config: &Self::Config,
+        _env: &Env,
+    ) -> Result<Self> {
+        if config.episodes_per_file == 0 {
+            return Err(anyhow::anyhow!("At least one episode must be written per file"));
+        }
+
+        // claim a subdirectory, which fails if another wrapper already did
+        create_dir_all(&config.directory)?;
+        let mut n = 0;
+        let directory = loop {
+            let directory = PathBuf::from(&config.directory).join(format!("recording_{n:03}"));
+            match create_dir(&directory) {
+                Ok(()) => break directory,
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => n += 1,
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        Ok(Self {
+            config: config.clone(),
+            directory,
+            prefix: String::new(),
+            clones: AtomicU64::new(0),
+            records: Vec::new(),
+            first_episode: 0,
+            episode: 0,
+            step: 0,
+            started: false,
+        })
+    }
+
+    fn reset(
+        &mut self,
+        env: &mut Env,
+        seed: u64,
+    ) -> Result<Self::Observation> {
+        if self.started {
+            self.episode += 1;
+            if self.episode - self.first_episode >= self.config.episodes_per_file as u64 {
+                self.flush()?;
+            }
+        }
+        self.started = true;
+        self.step = 0;
+
+        let observation = env.reset(seed)?;
+        self.records.push(StepRecord {
+            episode: self.episode,
+            step: 0,
+            observation: <Env::Observation>::to_vec(observation.clone()),
+            action: None,
+            reward: None,
+            terminated: false,
+            truncated: false,
+            info: env.step_info(),
+        });
+        Ok(observation)
+    }
+
+    fn step(
+        &mut self,
+        env: &mut Env,
+        action: Env::Action,
+    ) -> Result<Step<Self::Observation, Env::Action>> {
+        let step = env.step(action)?;
+        self.step += 1;
+        self.records.push(StepRecord {
+            episode: self.episode,
+            step: self.step,
+            observation: <Env::Observation>::to_vec(step.observation.clone()),
+            action: Some(<Env::Action>::to_vec(step.action.clone())),
+            reward: Some(step.reward),
+            terminated: step.terminated,
+            truncated: step.truncated,
+            info: env.step_info(),
+        });
+        Ok(step)
+    }
+
+    fn current_observation(
+        &self,
+        env: &Env,
+    ) -> Self::Observation {
+        env.current_observation()
+    }
+}
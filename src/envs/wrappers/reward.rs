@@ -0,0 +1,96 @@
+use {
+    super::{
+        super::{
+            Environment,
+            Step,
+        },
+        EnvWrapper,
+    },
+    serde::{
+        Serialize,
+        Deserialize,
+    },
+    anyhow::Result,
+};
+
+/// Scale the rewards of the inner environment by `scale`, and then clip them
+/// to `clip = (min, max)` if given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformReward {
+    pub scale: f64,
+    pub clip: Option<(f64, f64)>,
+}
+impl TransformReward {
+    pub fn transform(
+        &self,
+        reward: f64,
+    ) -> f64 {
+        let reward = reward * self.scale;
+        match self.clip {
+            Some((min, max)) => reward.clamp(min, max),
+            None => reward,
+        }
+    }
+}
+
+impl<Env: Environment> EnvWrapper<Env> for TransformReward {
+    type Config = Self;
+    type Observation = Env::Observation;
+
+    fn new(
+        config: &Self::Config,
+        _env: &Env,
+    ) -> Result<Self> {
+        if !config.scale.is_finite() {
+            return Err(anyhow::anyhow!("The reward scale must be finite"));
+        }
+        if let Some((min, max)) = config.clip {
+            if min > max {
+                return Err(anyhow::anyhow!("The reward clip range must have min <= max"));
+            }
+        }
+        Ok(config.clone())
+    }
+
+    fn reset(
+        &mut self,
+        env: &mut Env,
+        seed: u64,
+    ) -> Result<Self::Observation> {
+        env.reset(seed)
+    }
+
+    fn step(
+        &mut self,
+        env: &mut Env,
+        action: Env::Action,
+    ) -> Result<Step<Self::Observation, Env::Action>> {
+        let mut step = env.step(action)?;
+        step.reward = self.transform(step.reward);
+        Ok(step)
+    }
+
+    fn current_observation(
+        &self,
+        env: &Env,
+    ) -> Self::Observation {
+        env.current_observation()
+    }
+
+    /// The value range is scaled, and clipped to `timelimit` times the clip range.
+    fn value_range(
+        &self,
+        env: &Env,
+    ) -> (f64, f64) {
+        let (lo, hi) = env.value_range();
+        let (lo, hi) = (lo * self.scale, hi * self.scale);
+        let (lo, hi) = (lo.min(hi), lo.max(hi));
+        match self.clip {
+            Some((min, max)) => {
+                let steps = env.timelimit() as f64;
+                (lo.clamp(min * steps, max * steps), hi.clamp(min * steps, max * steps))
+            },
+            None => (lo, hi),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use {
+    super::{
+        super::{
+            Environment,
+            Step,
+        },
+        EnvWrapper,
+    },
+    anyhow::Result,
+};
+
+/// Override the timelimit of the inner environment.
+///
+/// Episodes are truncated after exactly `timelimit` steps, regardless of
+/// whether the inner environment would truncate them earlier or later. The
+/// value range of the inner environment is scaled with the timelimit.
+///
+/// The config is the new timelimit.
+#[derive(Debug, Clone)]
+pub struct TimeLimit {
+    timelimit: usize,
+    timestep: usize,
+}
+
+impl<Env: Environment> EnvWrapper<Env> for TimeLimit {
+    type Config = usize;
+    type Observation = Env::Observation;
+
+    fn new(
+        config: &Self::Config,
+        _env: &Env,
+    ) -> Result<Self> {
+        if *config == 0 {
+            return Err(anyhow::anyhow!("The timelimit must be positive"));
+        }
+        Ok(Self {
+            timelimit: *config,
+            timestep: 0,
+        })
+    }
+
+    fn reset(
+        &mut self,
+        env: &mut Env,
+        seed: u64,
+    ) -> Result<Self::Observation> {
+        self.timestep = 0;
+        env.reset(seed)
+    }
+
+    fn step(
+        &mut self,
+        env: &mut Env,
+        action: Env::Action,
+    ) -> Result<Step<Self::Observation, Env::Action>> {
+        let mut step = env.step(action)?;
+        self.timestep += 1;
+        step.truncated = !step.terminated && self.timestep >= self.timelimit;
+        Ok(step)
+    }
+
+    fn current_observation(
+        &self,
+        env: &Env,
+    ) -> Self::Observation {
+        env.current_observation()
+    }
+
+    fn timelimit(
+        &self,
+        _env: &Env,
+    ) -> usize {
+        self.timelimit
+    }
+
+    fn value_range(
+        &self,
+        env: &Env,
+    ) -> (f64, f64) {
+        let (lo, hi) = env.value_range();
+        let ratio = self.timelimit as f64 / env.timelimit().max(1) as f64;
+        (lo * ratio, hi * ratio)
+    }
+}